@@ -1,91 +1,775 @@
-use std::time::Duration;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+    rc::Rc,
+    time::Duration,
+};
 
-use crate::rendering::SpriteSheet;
+use ggez::{
+    graphics::{Image, Rect},
+    Context, GameResult,
+};
 
-pub struct SpriteSheetAnimation {
+use crate::{
+    rendering::{SpriteSheet, WorldDrawParams, WorldDrawable},
+    world::World,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlaybackMode {
+    Once,
+    Loop,
+    PingPong,
+    Reverse,
+}
+
+pub struct Playback {
     fps: f32,
-    spritesheet: SpriteSheet,
+    frame_count: u32,
     frame_duration: f32,
+    frame_durations: Option<Vec<f32>>,
+    mode: PlaybackMode,
+    speed: f32,
+    current_frame: u32,
     current_frame_time: f32,
+    direction: i32,
+    finished: bool,
 }
 
-impl SpriteSheetAnimation {
-    pub fn new(spritesheet: SpriteSheet, fps: f32) -> Self {
-        SpriteSheetAnimation {
-            spritesheet,
+impl Playback {
+    pub fn new(frame_count: u32, fps: f32) -> Self {
+        Self {
             fps,
+            frame_count,
             frame_duration: 1.0 / fps,
+            frame_durations: None,
+            mode: PlaybackMode::Loop,
+            speed: 1.0,
+            current_frame: 0,
             current_frame_time: 0.0,
+            direction: 1,
+            finished: false,
         }
     }
 
-    pub fn update(&mut self, deltatime: Duration) {
-        self.current_frame_time += deltatime.as_secs_f32();
-        if self.current_frame_time > self.frame_duration {
-            self.current_frame_time = 0.0;
-            self.spritesheet.set_next();
+    pub fn with_frame_durations(frame_durations: Vec<f32>) -> Self {
+        let total_duration: f32 = frame_durations.iter().sum();
+        let fps = if total_duration > 0.0 {
+            frame_durations.len() as f32 / total_duration
+        } else {
+            1.0
+        };
+        let mut playback = Self::new(frame_durations.len() as u32, fps);
+        playback.frame_durations = Some(frame_durations);
+        playback
+    }
+
+    pub fn update(&mut self, deltatime: Duration) -> Vec<u32> {
+        let mut entered_frames = Vec::new();
+        if self.finished || self.frame_count == 0 {
+            return entered_frames;
+        }
+        self.current_frame_time += deltatime.as_secs_f32() * self.speed;
+        loop {
+            let frame_duration = self.current_frame_duration();
+            if self.current_frame_time < frame_duration {
+                break;
+            }
+            self.current_frame_time -= frame_duration.max(0.0);
+            if !self.advance() {
+                self.finished = true;
+                self.current_frame_time = 0.0;
+                break;
+            }
+            entered_frames.push(self.current_frame);
+            if frame_duration <= 0.0 {
+                break;
+            }
         }
+        entered_frames
     }
 
     pub fn reset(&mut self) {
         self.current_frame_time = 0.0;
-        self.spritesheet.set_active(0);
+        self.finished = false;
+        self.direction = 1;
+        self.current_frame = match self.mode {
+            PlaybackMode::Reverse => self.frame_count.saturating_sub(1),
+            _ => 0,
+        };
+    }
+
+    pub fn current_frame_duration(&self) -> f32 {
+        self.frame_duration_at(self.current_frame)
+    }
+
+    pub fn total_duration(&self) -> f32 {
+        (0..self.frame_count)
+            .map(|frame| self.frame_duration_at(frame))
+            .sum()
+    }
+
+    pub fn normalized_time(&self) -> f32 {
+        let total_duration = self.total_duration();
+        if total_duration <= 0.0 {
+            return 0.0;
+        }
+        let elapsed: f32 = (0..self.current_frame)
+            .map(|frame| self.frame_duration_at(frame))
+            .sum();
+        ((elapsed + self.current_frame_time) / total_duration).clamp(0.0, 1.0)
+    }
+
+    pub fn set_normalized_time(&mut self, normalized_time: f32) {
+        self.finished = false;
+        let mut remaining = normalized_time.clamp(0.0, 1.0) * self.total_duration();
+        for frame in 0..self.frame_count {
+            let frame_duration = self.frame_duration_at(frame);
+            if remaining < frame_duration || frame + 1 == self.frame_count {
+                self.current_frame = frame;
+                self.current_frame_time = remaining.min(frame_duration).max(0.0);
+                return;
+            }
+            remaining -= frame_duration;
+        }
+    }
+
+    pub fn set_frame(&mut self, frame: u32) {
+        self.current_frame = frame.min(self.frame_count.saturating_sub(1));
+        self.current_frame_time = 0.0;
+    }
+
+    pub fn set_mode(&mut self, mode: PlaybackMode) {
+        self.mode = mode;
+        self.reset();
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
     }
 
     pub fn set_fps(&mut self, fps: f32) {
         self.fps = fps;
         self.frame_duration = 1.0 / fps;
+        self.frame_durations = None;
+    }
+
+    pub fn frame(&self) -> u32 {
+        self.current_frame
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count
+    }
+
+    pub fn frame_time(&self) -> f32 {
+        self.current_frame_time
+    }
+
+    pub fn mode(&self) -> PlaybackMode {
+        self.mode
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
     }
 
     pub fn fps(&self) -> f32 {
         self.fps
     }
 
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn frame_duration_at(&self, frame: u32) -> f32 {
+        self.frame_durations
+            .as_ref()
+            .and_then(|durations| durations.get(frame as usize))
+            .copied()
+            .unwrap_or(self.frame_duration)
+    }
+
+    fn advance(&mut self) -> bool {
+        let last = self.frame_count - 1;
+        match self.mode {
+            PlaybackMode::Loop => {
+                self.current_frame = (self.current_frame + 1) % self.frame_count;
+            }
+            PlaybackMode::Reverse => {
+                self.current_frame = (self.current_frame + last) % self.frame_count;
+            }
+            PlaybackMode::Once => {
+                if self.current_frame >= last {
+                    return false;
+                }
+                self.current_frame += 1;
+            }
+            PlaybackMode::PingPong => {
+                if last == 0 {
+                    return true;
+                }
+                let next = self.current_frame as i32 + self.direction;
+                if next < 0 || next > last as i32 {
+                    self.direction = -self.direction;
+                }
+                self.current_frame = (self.current_frame as i32 + self.direction) as u32;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationEvent {
+    pub name: String,
+    pub frame: u32,
+}
+
+#[derive(Default)]
+pub struct FrameEvents {
+    frame_events: HashMap<u32, Vec<String>>,
+    markers: HashMap<String, u32>,
+    marker_events: Vec<(String, String)>,
+}
+
+impl FrameEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_frame_event(&mut self, frame: u32, name: &str) {
+        self.frame_events
+            .entry(frame)
+            .or_default()
+            .push(name.to_string());
+    }
+
+    pub fn add_marker(&mut self, marker: &str, frame: u32) {
+        self.markers.insert(marker.to_string(), frame);
+    }
+
+    pub fn add_marker_event(&mut self, marker: &str, name: &str) {
+        self.marker_events
+            .push((marker.to_string(), name.to_string()));
+    }
+
+    pub fn marker(&self, marker: &str) -> Option<u32> {
+        self.markers.get(marker).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame_events.is_empty() && self.marker_events.is_empty()
+    }
+
+    pub fn collect(&self, frames: &[u32]) -> Vec<AnimationEvent> {
+        let mut events = Vec::new();
+        for frame in frames.iter().copied() {
+            if let Some(names) = self.frame_events.get(&frame) {
+                events.extend(names.iter().map(|name| AnimationEvent {
+                    name: name.clone(),
+                    frame,
+                }));
+            }
+            for (marker, name) in self.marker_events.iter() {
+                if self.marker(marker) == Some(frame) {
+                    events.push(AnimationEvent {
+                        name: name.clone(),
+                        frame,
+                    });
+                }
+            }
+        }
+        events
+    }
+}
+
+pub struct SpriteSheetAnimation {
+    spritesheet: SpriteSheet,
+    playback: Playback,
+    events: FrameEvents,
+    entered_frames: Vec<u32>,
+}
+
+impl SpriteSheetAnimation {
+    pub fn new(spritesheet: SpriteSheet, fps: f32) -> Self {
+        let playback = Playback::new(spritesheet.total_sprites(), fps);
+        SpriteSheetAnimation {
+            spritesheet,
+            playback,
+            events: FrameEvents::new(),
+            entered_frames: Vec::new(),
+        }
+    }
+
+    pub fn with_frame_durations(spritesheet: SpriteSheet, frame_durations: Vec<f32>) -> Self {
+        SpriteSheetAnimation {
+            spritesheet,
+            playback: Playback::with_frame_durations(frame_durations),
+            events: FrameEvents::new(),
+            entered_frames: Vec::new(),
+        }
+    }
+
+    pub fn current_frame_duration(&self) -> f32 {
+        self.playback.current_frame_duration()
+    }
+
+    pub fn update(&mut self, deltatime: Duration) -> Vec<AnimationEvent> {
+        self.entered_frames = self.playback.update(deltatime);
+        self.sync_frame();
+        self.events.collect(&self.entered_frames)
+    }
+
+    pub fn entered_frames(&self) -> &[u32] {
+        &self.entered_frames
+    }
+
+    pub fn normalized_time(&self) -> f32 {
+        self.playback.normalized_time()
+    }
+
+    pub fn set_normalized_time(&mut self, normalized_time: f32) {
+        self.playback.set_normalized_time(normalized_time);
+        self.sync_frame();
+    }
+
+    pub fn current_frame_events(&self) -> Vec<AnimationEvent> {
+        self.events.collect(&[self.playback.frame()])
+    }
+
+    pub fn events(&self) -> &FrameEvents {
+        &self.events
+    }
+
+    pub fn events_mut(&mut self) -> &mut FrameEvents {
+        &mut self.events
+    }
+
+    pub fn reset(&mut self) {
+        self.playback.reset();
+        self.entered_frames.clear();
+        self.sync_frame();
+    }
+
+    pub fn set_playback_mode(&mut self, mode: PlaybackMode) {
+        self.playback.set_mode(mode);
+        self.sync_frame();
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.playback.set_speed(speed);
+    }
+
+    pub fn set_fps(&mut self, fps: f32) {
+        self.playback.set_fps(fps);
+    }
+
+    pub fn fps(&self) -> f32 {
+        self.playback.fps()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.playback.is_finished()
+    }
+
+    pub fn playback(&self) -> &Playback {
+        &self.playback
+    }
+
     pub fn get_drawable(&self) -> &SpriteSheet {
         &self.spritesheet
     }
+
+    pub fn set_image(&mut self, image: Rc<Image>) {
+        self.spritesheet.set_image(image);
+    }
+
+    fn sync_frame(&mut self) {
+        if self.spritesheet.active() != self.playback.frame() {
+            self.spritesheet.set_active(self.playback.frame());
+        }
+    }
+}
+
+pub fn cross_fade_alphas(alpha: f32, weight: f32) -> (f32, f32) {
+    (alpha, alpha * weight.clamp(0.0, 1.0))
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Parameters {
+    floats: HashMap<String, f32>,
+    bools: HashMap<String, bool>,
+    triggers: HashSet<String>,
+}
+
+impl Parameters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_float(&mut self, name: &str, value: f32) {
+        self.floats.insert(name.to_string(), value);
+    }
+
+    pub fn set_bool(&mut self, name: &str, value: bool) {
+        self.bools.insert(name.to_string(), value);
+    }
+
+    pub fn set_trigger(&mut self, name: &str) {
+        self.triggers.insert(name.to_string());
+    }
+
+    pub fn reset_trigger(&mut self, name: &str) {
+        self.triggers.remove(name);
+    }
+
+    pub fn float(&self, name: &str) -> f32 {
+        self.floats.get(name).copied().unwrap_or(0.0)
+    }
+
+    pub fn bool(&self, name: &str) -> bool {
+        self.bools.get(name).copied().unwrap_or(false)
+    }
+
+    pub fn is_triggered(&self, name: &str) -> bool {
+        self.triggers.contains(name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    Greater(String, f32),
+    Less(String, f32),
+    IsTrue(String),
+    IsFalse(String),
+    Trigger(String),
+}
+
+impl Condition {
+    pub fn greater(name: &str, value: f32) -> Self {
+        Condition::Greater(name.to_string(), value)
+    }
+
+    pub fn less(name: &str, value: f32) -> Self {
+        Condition::Less(name.to_string(), value)
+    }
+
+    pub fn is_true(name: &str) -> Self {
+        Condition::IsTrue(name.to_string())
+    }
+
+    pub fn is_false(name: &str) -> Self {
+        Condition::IsFalse(name.to_string())
+    }
+
+    pub fn trigger(name: &str) -> Self {
+        Condition::Trigger(name.to_string())
+    }
+
+    pub fn is_met(&self, parameters: &Parameters) -> bool {
+        match self {
+            Condition::Greater(name, value) => parameters.float(name) > *value,
+            Condition::Less(name, value) => parameters.float(name) < *value,
+            Condition::IsTrue(name) => parameters.bool(name),
+            Condition::IsFalse(name) => !parameters.bool(name),
+            Condition::Trigger(name) => parameters.is_triggered(name),
+        }
+    }
 }
 
-struct Rule<T> {
-    to: usize,
-    trigger: Box<dyn Fn(T) -> bool>,
+pub type RuleId = usize;
+
+struct Rule<T, S> {
+    id: RuleId,
+    to: S,
+    priority: i32,
+    trigger: Box<dyn Fn(&T) -> bool>,
 }
-pub struct StateMachine<T> {
-    rules: Vec<Vec<Rule<T>>>,
-    current_state: usize,
+
+const DEFAULT_HISTORY_LIMIT: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transition<S> {
+    pub from: S,
+    pub to: S,
+    pub rule: Option<RuleId>,
+    pub time: f32,
 }
 
-impl<T: Clone> StateMachine<T> {
+type TransitionHook<S> = Box<dyn FnMut(&Transition<S>)>;
+type UpdateHook = Box<dyn FnMut(Duration, f32)>;
+
+pub struct StateMachine<T, S = usize> {
+    rules: HashMap<S, Vec<Rule<T, S>>>,
+    any_rules: Vec<Rule<T, S>>,
+    rule_triggers: HashMap<RuleId, Vec<String>>,
+    rule_cooldowns: HashMap<RuleId, f32>,
+    rule_last_fired: HashMap<RuleId, f32>,
+    min_times: HashMap<S, f32>,
+    enter_hooks: HashMap<S, Vec<TransitionHook<S>>>,
+    exit_hooks: HashMap<S, Vec<TransitionHook<S>>>,
+    update_hooks: HashMap<S, Vec<UpdateHook>>,
+    history: VecDeque<Transition<S>>,
+    history_limit: usize,
+    initial_state: S,
+    current_state: S,
+    time_in_state: f32,
+    elapsed: f32,
+    next_rule_id: RuleId,
+}
+
+impl<T> StateMachine<T> {
     pub fn new(state_count: usize) -> Self {
+        let mut state_machine = Self::with_initial_state(0);
+        state_machine.rules.reserve(state_count);
+        state_machine
+    }
+}
+
+impl<T, S: Copy + Eq + Hash> StateMachine<T, S> {
+    pub fn with_initial_state(initial_state: S) -> Self {
         Self {
-            current_state: 0,
-            rules: (0..state_count).into_iter().map(|_| Vec::new()).collect(),
+            rules: HashMap::new(),
+            any_rules: Vec::new(),
+            rule_triggers: HashMap::new(),
+            rule_cooldowns: HashMap::new(),
+            rule_last_fired: HashMap::new(),
+            min_times: HashMap::new(),
+            enter_hooks: HashMap::new(),
+            exit_hooks: HashMap::new(),
+            update_hooks: HashMap::new(),
+            history: VecDeque::new(),
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            initial_state,
+            current_state: initial_state,
+            time_in_state: 0.0,
+            elapsed: 0.0,
+            next_rule_id: 0,
         }
     }
 
-    pub fn add_rule<F>(&mut self, from: usize, to: usize, callback: F)
+    pub fn add_prioritized_rule<F>(&mut self, from: S, to: S, priority: i32, callback: F) -> RuleId
     where
-        F: Fn(T) -> bool + 'static,
+        F: Fn(&T) -> bool + 'static,
     {
-        self.rules[from].push(Rule {
-            to,
-            trigger: Box::new(callback),
-        });
+        let rule = self.create_rule(to, priority, Box::new(callback));
+        let id = rule.id;
+        insert_by_priority(self.rules.entry(from).or_default(), rule);
+        id
     }
 
-    pub fn update_once(&mut self, monitor: T) -> bool {
-        for rule in self.rules[self.current_state].iter() {
-            if (rule.trigger)(monitor.clone()) {
-                self.current_state = rule.to;
-                return true;
+    pub fn add_any_rule<F>(&mut self, to: S, priority: i32, callback: F) -> RuleId
+    where
+        F: Fn(&T) -> bool + 'static,
+    {
+        let rule = self.create_rule(to, priority, Box::new(callback));
+        let id = rule.id;
+        insert_by_priority(&mut self.any_rules, rule);
+        id
+    }
+
+    pub fn set_rule_cooldown(&mut self, rule: RuleId, cooldown: Duration) {
+        self.rule_cooldowns.insert(rule, cooldown.as_secs_f32());
+    }
+
+    pub fn set_min_time_in_state(&mut self, state: S, min_time: Duration) {
+        self.min_times.insert(state, min_time.as_secs_f32());
+    }
+
+    pub fn on_enter<F>(&mut self, state: S, callback: F)
+    where
+        F: FnMut(&Transition<S>) + 'static,
+    {
+        self.enter_hooks
+            .entry(state)
+            .or_default()
+            .push(Box::new(callback));
+    }
+
+    pub fn on_exit<F>(&mut self, state: S, callback: F)
+    where
+        F: FnMut(&Transition<S>) + 'static,
+    {
+        self.exit_hooks
+            .entry(state)
+            .or_default()
+            .push(Box::new(callback));
+    }
+
+    pub fn on_update<F>(&mut self, state: S, callback: F)
+    where
+        F: FnMut(Duration, f32) + 'static,
+    {
+        self.update_hooks
+            .entry(state)
+            .or_default()
+            .push(Box::new(callback));
+    }
+
+    pub fn tick(&mut self, deltatime: Duration) {
+        self.elapsed += deltatime.as_secs_f32();
+        self.time_in_state += deltatime.as_secs_f32();
+        if let Some(hooks) = self.update_hooks.get_mut(&self.current_state) {
+            for hook in hooks.iter_mut() {
+                hook(deltatime, self.time_in_state);
             }
         }
-        false
+    }
+
+    pub fn step(&mut self, monitor: &T) -> Option<RuleId> {
+        let current_state = self.current_state;
+        let min_time = self.min_times.get(&current_state).copied().unwrap_or(0.0);
+        if self.time_in_state < min_time {
+            return None;
+        }
+
+        let empty = Vec::new();
+        let state_rules = self.rules.get(&current_state).unwrap_or(&empty);
+        let any_rules: Vec<_> = self
+            .any_rules
+            .iter()
+            .filter(|rule| rule.to != current_state)
+            .collect();
+
+        let mut state_iter = state_rules.iter().peekable();
+        let mut any_iter = any_rules.into_iter().peekable();
+        let fired = loop {
+            let rule = match (state_iter.peek(), any_iter.peek()) {
+                (Some(state_rule), Some(any_rule)) => {
+                    if any_rule.priority > state_rule.priority {
+                        any_iter.next()
+                    } else {
+                        state_iter.next()
+                    }
+                }
+                (Some(_), None) => state_iter.next(),
+                (None, Some(_)) => any_iter.next(),
+                (None, None) => break None,
+            };
+            if let Some(rule) = rule {
+                let cooling_down = match (
+                    self.rule_cooldowns.get(&rule.id),
+                    self.rule_last_fired.get(&rule.id),
+                ) {
+                    (Some(cooldown), Some(last_fired)) => self.elapsed - last_fired < *cooldown,
+                    _ => false,
+                };
+                if !cooling_down && (rule.trigger)(monitor) {
+                    break Some((rule.id, rule.to));
+                }
+            }
+        };
+
+        let (id, to) = fired?;
+        self.rule_last_fired.insert(id, self.elapsed);
+        self.enter_state(to, Some(id));
+        Some(id)
+    }
+
+    pub fn transition_to(&mut self, state: S) {
+        self.enter_state(state, None);
+    }
+
+    pub fn state(&self) -> S {
+        self.current_state
+    }
+
+    pub fn set_state(&mut self, state: S) {
+        self.current_state = state;
+        self.time_in_state = 0.0;
+    }
+
+    pub fn initial_state(&self) -> S {
+        self.initial_state
+    }
+
+    pub fn reset(&mut self) {
+        self.set_state(self.initial_state);
+    }
+
+    pub fn time_in_state(&self) -> f32 {
+        self.time_in_state
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn history(&self) -> &VecDeque<Transition<S>> {
+        &self.history
+    }
+
+    pub fn last_transition(&self) -> Option<&Transition<S>> {
+        self.history.back()
+    }
+
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history_limit = limit;
+        while self.history.len() > limit {
+            self.history.pop_front();
+        }
+    }
+
+    fn enter_state(&mut self, to: S, rule: Option<RuleId>) {
+        let transition = Transition {
+            from: self.current_state,
+            to,
+            rule,
+            time: self.elapsed,
+        };
+        if let Some(hooks) = self.exit_hooks.get_mut(&transition.from) {
+            for hook in hooks.iter_mut() {
+                hook(&transition);
+            }
+        }
+        self.current_state = to;
+        self.time_in_state = 0.0;
+        if self.history_limit > 0 {
+            if self.history.len() == self.history_limit {
+                self.history.pop_front();
+            }
+            self.history.push_back(transition);
+        }
+        if let Some(hooks) = self.enter_hooks.get_mut(&to) {
+            for hook in hooks.iter_mut() {
+                hook(&transition);
+            }
+        }
+    }
+
+    fn create_rule(
+        &mut self,
+        to: S,
+        priority: i32,
+        trigger: Box<dyn Fn(&T) -> bool>,
+    ) -> Rule<T, S> {
+        let id = self.next_rule_id;
+        self.next_rule_id += 1;
+        Rule {
+            id,
+            to,
+            priority,
+            trigger,
+        }
+    }
+}
+
+impl<T: Clone + 'static, S: Copy + Eq + Hash> StateMachine<T, S> {
+    pub fn add_rule<F>(&mut self, from: S, to: S, callback: F) -> RuleId
+    where
+        F: Fn(T) -> bool + 'static,
+    {
+        self.add_prioritized_rule(from, to, 0, move |monitor: &T| callback(monitor.clone()))
+    }
+
+    pub fn update_once(&mut self, monitor: T) -> bool {
+        self.step(&monitor).is_some()
     }
 
     pub fn update_full(&mut self, monitor: T) -> bool {
         let mut updated = false;
-        while self.update_once(monitor.clone()) {
+        while self.step(&monitor).is_some() {
             updated = true;
         }
         updated
@@ -93,50 +777,669 @@ impl<T: Clone> StateMachine<T> {
 
     pub fn update_with_limit(&mut self, monitor: T, limit: u32) -> bool {
         for _ in 0..limit {
-            if self.update_once(monitor.clone()) {
+            if self.step(&monitor).is_some() {
                 return true;
             }
         }
         false
     }
+}
 
-    pub fn state(&self) -> usize {
-        self.current_state
+impl<S: Copy + Eq + Hash> StateMachine<Parameters, S> {
+    pub fn add_transition(&mut self, from: S, to: S, conditions: Vec<Condition>) -> RuleId {
+        self.add_prioritized_transition(from, to, 0, conditions)
     }
 
-    pub fn set_state(&mut self, state: usize) {
-        self.current_state = state;
+    pub fn add_prioritized_transition(
+        &mut self,
+        from: S,
+        to: S,
+        priority: i32,
+        conditions: Vec<Condition>,
+    ) -> RuleId {
+        let triggers = condition_triggers(&conditions);
+        let id = self.add_prioritized_rule(from, to, priority, move |parameters| {
+            conditions
+                .iter()
+                .all(|condition| condition.is_met(parameters))
+        });
+        self.rule_triggers.insert(id, triggers);
+        id
+    }
+
+    pub fn add_any_transition(
+        &mut self,
+        to: S,
+        priority: i32,
+        conditions: Vec<Condition>,
+    ) -> RuleId {
+        let triggers = condition_triggers(&conditions);
+        let id = self.add_any_rule(to, priority, move |parameters| {
+            conditions
+                .iter()
+                .all(|condition| condition.is_met(parameters))
+        });
+        self.rule_triggers.insert(id, triggers);
+        id
+    }
+
+    pub fn update_parameters(&mut self, parameters: &mut Parameters) -> bool {
+        match self.step(parameters) {
+            Some(id) => {
+                if let Some(triggers) = self.rule_triggers.get(&id) {
+                    for trigger in triggers.iter() {
+                        parameters.reset_trigger(trigger);
+                    }
+                }
+                true
+            }
+            None => false,
+        }
     }
 }
 
-pub struct SpriteAnimator<T> {
-    animations: Vec<SpriteSheetAnimation>,
-    state_machine: StateMachine<T>,
+pub struct HierarchicalStateMachine<T, S> {
+    machine: StateMachine<T, S>,
+    sub_machines: HashMap<S, HierarchicalStateMachine<T, S>>,
 }
 
-impl<T: Clone> SpriteAnimator<T> {
-    pub fn from_animations(animations: Vec<SpriteSheetAnimation>) -> Self {
+impl<T, S: Copy + Eq + Hash> HierarchicalStateMachine<T, S> {
+    pub fn new(machine: StateMachine<T, S>) -> Self {
         Self {
-            state_machine: StateMachine::<T>::new(animations.len()),
-            animations,
+            machine,
+            sub_machines: HashMap::new(),
+        }
+    }
+
+    pub fn with_initial_state(initial_state: S) -> Self {
+        Self::new(StateMachine::with_initial_state(initial_state))
+    }
+
+    pub fn add_sub_machine(&mut self, parent: S, sub_machine: HierarchicalStateMachine<T, S>) {
+        self.sub_machines.insert(parent, sub_machine);
+    }
+
+    pub fn step(&mut self, monitor: &T) -> bool {
+        self.step_with(&mut |machine| machine.step(monitor).is_some())
+    }
+
+    pub fn tick(&mut self, deltatime: Duration) {
+        self.machine.tick(deltatime);
+        if let Some(sub_machine) = self.sub_machines.get_mut(&self.machine.state()) {
+            sub_machine.tick(deltatime);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.machine.reset();
+        if let Some(sub_machine) = self.sub_machines.get_mut(&self.machine.state()) {
+            sub_machine.reset();
         }
     }
 
-    pub fn update(&mut self, monitor: T, deltatime: Duration) {
-        self.animations[self.state_machine.state()].update(deltatime);
-        if self.state_machine.update_once(monitor) {
-            self.animations[self.state_machine.state()].reset();
+    pub fn state(&self) -> S {
+        match self.active_sub_machine() {
+            Some(sub_machine) => sub_machine.state(),
+            None => self.machine.state(),
+        }
+    }
+
+    pub fn active_path(&self) -> Vec<S> {
+        let mut path = vec![self.machine.state()];
+        if let Some(sub_machine) = self.active_sub_machine() {
+            path.append(&mut sub_machine.active_path());
+        }
+        path
+    }
+
+    pub fn is_in(&self, state: S) -> bool {
+        self.machine.state() == state
+            || self
+                .active_sub_machine()
+                .is_some_and(|sub_machine| sub_machine.is_in(state))
+    }
+
+    pub fn machine(&self) -> &StateMachine<T, S> {
+        &self.machine
+    }
+
+    pub fn machine_mut(&mut self) -> &mut StateMachine<T, S> {
+        &mut self.machine
+    }
+
+    pub fn sub_machine(&self, parent: S) -> Option<&HierarchicalStateMachine<T, S>> {
+        self.sub_machines.get(&parent)
+    }
+
+    pub fn sub_machine_mut(&mut self, parent: S) -> Option<&mut HierarchicalStateMachine<T, S>> {
+        self.sub_machines.get_mut(&parent)
+    }
+
+    fn active_sub_machine(&self) -> Option<&HierarchicalStateMachine<T, S>> {
+        self.sub_machines.get(&self.machine.state())
+    }
+
+    fn step_with(&mut self, step: &mut dyn FnMut(&mut StateMachine<T, S>) -> bool) -> bool {
+        if step(&mut self.machine) {
+            if let Some(sub_machine) = self.sub_machines.get_mut(&self.machine.state()) {
+                sub_machine.reset();
+                sub_machine.step_with(step);
+            }
+            return true;
+        }
+        match self.sub_machines.get_mut(&self.machine.state()) {
+            Some(sub_machine) => sub_machine.step_with(step),
+            None => false,
+        }
+    }
+}
+
+impl<S: Copy + Eq + Hash> HierarchicalStateMachine<Parameters, S> {
+    pub fn update_parameters(&mut self, parameters: &mut Parameters) -> bool {
+        self.step_with(&mut |machine| machine.update_parameters(parameters))
+    }
+}
+
+fn insert_by_priority<T, S>(rules: &mut Vec<Rule<T, S>>, rule: Rule<T, S>) {
+    let index = rules
+        .iter()
+        .position(|existing| existing.priority < rule.priority)
+        .unwrap_or(rules.len());
+    rules.insert(index, rule);
+}
+
+fn condition_triggers(conditions: &[Condition]) -> Vec<String> {
+    conditions
+        .iter()
+        .filter_map(|condition| match condition {
+            Condition::Trigger(name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BlendSettings {
+    pub fade_duration: f32,
+    pub sync_time: bool,
+    pub hold_until_finished: bool,
+}
+
+impl BlendSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cross_fade(duration: Duration) -> Self {
+        Self {
+            fade_duration: duration.as_secs_f32(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_sync_time(mut self) -> Self {
+        self.sync_time = true;
+        self
+    }
+
+    pub fn with_hold_until_finished(mut self) -> Self {
+        self.hold_until_finished = true;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CrossFade {
+    from: usize,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl CrossFade {
+    pub fn new(from: usize, duration: f32) -> Self {
+        Self {
+            from,
+            duration,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, deltatime: Duration) {
+        self.elapsed = (self.elapsed + deltatime.as_secs_f32()).min(self.duration);
+    }
+
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    pub fn weight(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        self.elapsed / self.duration
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+type EventCallback = Box<dyn FnMut(&AnimationEvent)>;
+
+pub struct SpriteAnimator<S = usize> {
+    states: HashMap<S, usize>,
+    animation_states: Vec<S>,
+    animations: Vec<SpriteSheetAnimation>,
+    state_machine: HierarchicalStateMachine<Parameters, S>,
+    parameters: Parameters,
+    callbacks: HashMap<String, Vec<EventCallback>>,
+    blends: HashMap<(S, S), BlendSettings>,
+    default_blend: BlendSettings,
+    active: Option<usize>,
+    pending: Option<usize>,
+    fade: Option<CrossFade>,
+}
+
+impl SpriteAnimator {
+    pub fn from_animations(animations: Vec<SpriteSheetAnimation>) -> Option<Self> {
+        Self::from_states(animations.into_iter().enumerate().collect())
+    }
+}
+
+impl<S: Copy + Eq + Hash> SpriteAnimator<S> {
+    pub fn from_states(states: Vec<(S, SpriteSheetAnimation)>) -> Option<Self> {
+        let initial_state = states.first().map(|(state, _)| *state)?;
+        Some(Self::with_state_machine(
+            states,
+            HierarchicalStateMachine::with_initial_state(initial_state),
+        ))
+    }
+
+    pub fn with_state_machine(
+        states: Vec<(S, SpriteSheetAnimation)>,
+        state_machine: HierarchicalStateMachine<Parameters, S>,
+    ) -> Self {
+        let mut state_indices = HashMap::new();
+        let mut animation_states = Vec::with_capacity(states.len());
+        let mut animations = Vec::with_capacity(states.len());
+        for (index, (state, animation)) in states.into_iter().enumerate() {
+            state_indices.insert(state, index);
+            animation_states.push(state);
+            animations.push(animation);
+        }
+        let mut animator = Self {
+            states: state_indices,
+            animation_states,
+            animations,
+            state_machine,
+            parameters: Parameters::new(),
+            callbacks: HashMap::new(),
+            blends: HashMap::new(),
+            default_blend: BlendSettings::new(),
+            active: None,
+            pending: None,
+            fade: None,
+        };
+        animator.active = animator.current_animation_index();
+        animator
+    }
+
+    pub fn update(&mut self, deltatime: Duration) -> Vec<AnimationEvent> {
+        let mut events = match self.active {
+            Some(index) => self.animations[index].update(deltatime),
+            None => Vec::new(),
+        };
+        if let Some(fade) = self.fade.as_mut() {
+            fade.update(deltatime);
+            self.animations[fade.from()].update(deltatime);
+            if fade.is_finished() {
+                self.fade = None;
+            }
+        }
+
+        self.state_machine.tick(deltatime);
+        if self.state_machine.update_parameters(&mut self.parameters) {
+            self.pending = self.current_animation_index();
+        }
+        if let Some(to) = self.pending {
+            let settings = self.blend_settings(to);
+            let ready = match self.active {
+                Some(index) if settings.hold_until_finished => {
+                    let animation = &self.animations[index];
+                    animation.is_finished() || !animation.entered_frames().is_empty()
+                }
+                _ => true,
+            };
+            if ready {
+                self.pending = None;
+                self.switch_to(to, settings, &mut events);
+            }
+        }
+        for event in events.iter() {
+            if let Some(callbacks) = self.callbacks.get_mut(&event.name) {
+                for callback in callbacks.iter_mut() {
+                    callback(event);
+                }
+            }
         }
+        events
     }
 
-    pub fn add_rule<F>(&mut self, from: usize, to: usize, callback: F)
+    pub fn on_event<F>(&mut self, name: &str, callback: F)
     where
-        F: Fn(T) -> bool + 'static,
+        F: FnMut(&AnimationEvent) + 'static,
     {
-        self.state_machine.add_rule(from, to, callback);
+        self.callbacks
+            .entry(name.to_string())
+            .or_default()
+            .push(Box::new(callback));
     }
 
-    pub fn get_drawable(&self) -> &SpriteSheet {
-        self.animations[self.state_machine.state()].get_drawable()
+    pub fn add_transition(&mut self, from: S, to: S, conditions: Vec<Condition>) -> RuleId {
+        self.state_machine
+            .machine_mut()
+            .add_transition(from, to, conditions)
+    }
+
+    pub fn add_prioritized_transition(
+        &mut self,
+        from: S,
+        to: S,
+        priority: i32,
+        conditions: Vec<Condition>,
+    ) -> RuleId {
+        self.state_machine
+            .machine_mut()
+            .add_prioritized_transition(from, to, priority, conditions)
+    }
+
+    pub fn add_any_transition(
+        &mut self,
+        to: S,
+        priority: i32,
+        conditions: Vec<Condition>,
+    ) -> RuleId {
+        self.state_machine
+            .machine_mut()
+            .add_any_transition(to, priority, conditions)
+    }
+
+    pub fn add_rule<F>(&mut self, from: S, to: S, callback: F) -> RuleId
+    where
+        F: Fn(&Parameters) -> bool + 'static,
+    {
+        self.state_machine
+            .machine_mut()
+            .add_prioritized_rule(from, to, 0, callback)
+    }
+
+    pub fn add_sub_machine(
+        &mut self,
+        parent: S,
+        sub_machine: HierarchicalStateMachine<Parameters, S>,
+    ) {
+        self.state_machine.add_sub_machine(parent, sub_machine);
+    }
+
+    pub fn parameters(&self) -> &Parameters {
+        &self.parameters
+    }
+
+    pub fn parameters_mut(&mut self) -> &mut Parameters {
+        &mut self.parameters
+    }
+
+    pub fn set_float(&mut self, name: &str, value: f32) {
+        self.parameters.set_float(name, value);
+    }
+
+    pub fn set_bool(&mut self, name: &str, value: bool) {
+        self.parameters.set_bool(name, value);
+    }
+
+    pub fn set_trigger(&mut self, name: &str) {
+        self.parameters.set_trigger(name);
+    }
+
+    pub fn state(&self) -> S {
+        self.state_machine.state()
+    }
+
+    pub fn is_in(&self, state: S) -> bool {
+        self.state_machine.is_in(state)
+    }
+
+    pub fn state_machine(&self) -> &HierarchicalStateMachine<Parameters, S> {
+        &self.state_machine
+    }
+
+    pub fn state_machine_mut(&mut self) -> &mut HierarchicalStateMachine<Parameters, S> {
+        &mut self.state_machine
+    }
+
+    pub fn animation_mut(&mut self, state: S) -> Option<&mut SpriteSheetAnimation> {
+        let index = *self.states.get(&state)?;
+        self.animations.get_mut(index)
+    }
+
+    pub fn set_blend(&mut self, from: S, to: S, settings: BlendSettings) {
+        self.blends.insert((from, to), settings);
+    }
+
+    pub fn set_default_blend(&mut self, settings: BlendSettings) {
+        self.default_blend = settings;
+    }
+
+    pub fn cross_fade(&self) -> Option<&CrossFade> {
+        self.fade.as_ref()
+    }
+
+    pub fn is_transition_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn drawable(&self) -> Option<&SpriteSheet> {
+        let index = self.active?;
+        Some(self.animations[index].get_drawable())
+    }
+
+    pub fn fading_drawable(&self) -> Option<(&SpriteSheet, f32)> {
+        let fade = self.fade.as_ref()?;
+        Some((
+            self.animations[fade.from()].get_drawable(),
+            1.0 - fade.weight(),
+        ))
+    }
+
+    pub fn draw_in_world(&self, ctx: &mut Context, world: &World, rect: Rect) -> GameResult {
+        self.draw_in_world_with_params(ctx, world, rect, WorldDrawParams::default())
+    }
+
+    pub fn draw_in_world_with_params(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        rect: Rect,
+        params: WorldDrawParams,
+    ) -> GameResult {
+        let alpha = match self.fading_drawable() {
+            Some((drawable, outgoing_weight)) => {
+                let (outgoing, incoming) = cross_fade_alphas(params.alpha, 1.0 - outgoing_weight);
+                if let Some(instance) = drawable.instance() {
+                    instance.draw_in_world_with_params(ctx, world, rect, params.alpha(outgoing))?;
+                }
+                incoming
+            }
+            None => params.alpha,
+        };
+        match self.drawable().and_then(SpriteSheet::instance) {
+            Some(instance) => {
+                instance.draw_in_world_with_params(ctx, world, rect, params.alpha(alpha))
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn get_drawable(&self) -> Option<&SpriteSheet> {
+        self.drawable()
+    }
+
+    fn current_animation_index(&self) -> Option<usize> {
+        self.state_machine
+            .active_path()
+            .iter()
+            .rev()
+            .find_map(|state| self.states.get(state).copied())
+    }
+
+    fn blend_settings(&self, to: usize) -> BlendSettings {
+        self.active
+            .and_then(|from| {
+                self.blends
+                    .get(&(self.animation_states[from], self.animation_states[to]))
+            })
+            .copied()
+            .unwrap_or(self.default_blend)
+    }
+
+    fn switch_to(&mut self, to: usize, settings: BlendSettings, events: &mut Vec<AnimationEvent>) {
+        let from = self.active.filter(|from| *from != to);
+        let normalized_time = from.map(|from| self.animations[from].normalized_time());
+        let animation = &mut self.animations[to];
+        animation.reset();
+        if let Some(normalized_time) = normalized_time.filter(|_| settings.sync_time) {
+            animation.set_normalized_time(normalized_time);
+        }
+        events.append(&mut animation.current_frame_events());
+        self.fade = from
+            .filter(|_| settings.fade_duration > 0.0)
+            .map(|from| CrossFade::new(from, settings.fade_duration));
+        self.active = Some(to);
+    }
+}
+
+pub struct AnimationLayer<S = usize> {
+    name: String,
+    animator: SpriteAnimator<S>,
+    enabled: bool,
+}
+
+impl<S: Copy + Eq + Hash> AnimationLayer<S> {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn animator(&self) -> &SpriteAnimator<S> {
+        &self.animator
+    }
+
+    pub fn animator_mut(&mut self) -> &mut SpriteAnimator<S> {
+        &mut self.animator
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+pub struct LayeredAnimator<S = usize> {
+    layers: Vec<AnimationLayer<S>>,
+    parameters: Parameters,
+}
+
+impl<S: Copy + Eq + Hash> Default for LayeredAnimator<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Copy + Eq + Hash> LayeredAnimator<S> {
+    pub fn new() -> Self {
+        Self {
+            layers: Vec::new(),
+            parameters: Parameters::new(),
+        }
+    }
+
+    pub fn add_layer(&mut self, name: &str, animator: SpriteAnimator<S>) -> usize {
+        self.layers.push(AnimationLayer {
+            name: name.to_string(),
+            animator,
+            enabled: true,
+        });
+        self.layers.len() - 1
+    }
+
+    pub fn update(&mut self, deltatime: Duration) -> Vec<AnimationEvent> {
+        let mut events = Vec::new();
+        for layer in self.layers.iter_mut().filter(|layer| layer.enabled) {
+            std::mem::swap(&mut self.parameters, &mut layer.animator.parameters);
+            events.append(&mut layer.animator.update(deltatime));
+            std::mem::swap(&mut self.parameters, &mut layer.animator.parameters);
+        }
+        events
+    }
+
+    pub fn draw_in_world(&self, ctx: &mut Context, world: &World, rect: Rect) -> GameResult {
+        self.draw_in_world_with_params(ctx, world, rect, WorldDrawParams::default())
+    }
+
+    pub fn draw_in_world_with_params(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        rect: Rect,
+        params: WorldDrawParams,
+    ) -> GameResult {
+        for layer in self.layers.iter().filter(|layer| layer.enabled) {
+            layer
+                .animator
+                .draw_in_world_with_params(ctx, world, rect, params)?;
+        }
+        Ok(())
+    }
+
+    pub fn drawables(&self) -> Vec<&SpriteSheet> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.enabled)
+            .filter_map(|layer| layer.animator.drawable())
+            .collect()
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&AnimationLayer<S>> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut AnimationLayer<S>> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    pub fn layers(&self) -> &[AnimationLayer<S>] {
+        &self.layers
+    }
+
+    pub fn parameters(&self) -> &Parameters {
+        &self.parameters
+    }
+
+    pub fn parameters_mut(&mut self) -> &mut Parameters {
+        &mut self.parameters
+    }
+
+    pub fn set_float(&mut self, name: &str, value: f32) {
+        self.parameters.set_float(name, value);
+    }
+
+    pub fn set_bool(&mut self, name: &str, value: bool) {
+        self.parameters.set_bool(name, value);
+    }
+
+    pub fn set_trigger(&mut self, name: &str) {
+        self.parameters.set_trigger(name);
     }
 }
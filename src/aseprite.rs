@@ -0,0 +1,168 @@
+use std::{collections::HashMap, path::Path, rc::Rc};
+
+use ggez::{graphics::Image, Context, GameResult};
+use serde_json::Value;
+
+use crate::{
+    animation::SpriteSheetAnimation,
+    atlas::{atlas_error, parse_atlas_json, read_to_string, AtlasFrame},
+    rendering::{SpriteFrame, SpriteSheet},
+};
+
+pub const DEFAULT_TAG: &str = "default";
+const DEFAULT_FRAME_DURATION: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TagDirection {
+    Forward,
+    Reverse,
+    PingPong,
+    PingPongReverse,
+}
+
+pub struct AsepriteTag {
+    pub name: String,
+    pub from: usize,
+    pub to: usize,
+    pub direction: TagDirection,
+}
+
+pub struct AsepriteSheet {
+    pub frames: Vec<AtlasFrame>,
+    pub tags: Vec<AsepriteTag>,
+}
+
+impl AsepriteSheet {
+    pub fn from_json(json: &str) -> GameResult<Self> {
+        let frames = parse_atlas_json(json)?;
+        let root: Value = serde_json::from_str(json)
+            .map_err(|err| atlas_error(format!("invalid json: {}", err)))?;
+        let mut tags = root
+            .get("meta")
+            .and_then(|meta| meta.get("frameTags"))
+            .and_then(Value::as_array)
+            .map(|tags| tags.iter().map(parse_tag).collect::<GameResult<Vec<_>>>())
+            .transpose()?
+            .unwrap_or_default();
+
+        for tag in tags.iter() {
+            if tag.from > tag.to || tag.to >= frames.len() {
+                return Err(atlas_error(format!(
+                    "tag {} has invalid range {}..={}",
+                    tag.name, tag.from, tag.to
+                )));
+            }
+        }
+        if tags.is_empty() && !frames.is_empty() {
+            tags.push(AsepriteTag {
+                name: DEFAULT_TAG.to_string(),
+                from: 0,
+                to: frames.len() - 1,
+                direction: TagDirection::Forward,
+            });
+        }
+
+        Ok(Self { frames, tags })
+    }
+
+    pub fn load<P: AsRef<Path>>(ctx: &mut Context, json_path: P) -> GameResult<Self> {
+        let json = read_to_string(ctx, json_path)?;
+        Self::from_json(&json)
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&AsepriteTag> {
+        self.tags.iter().find(|tag| tag.name == name)
+    }
+
+    pub fn frame_sequence(&self, tag: &AsepriteTag) -> Vec<usize> {
+        let forward: Vec<usize> = (tag.from..=tag.to).collect();
+        let backward: Vec<usize> = forward.iter().rev().copied().collect();
+        let ping_pong = |first: &[usize], second: &[usize]| {
+            let mut sequence = first.to_vec();
+            if second.len() > 2 {
+                sequence.extend_from_slice(&second[1..second.len() - 1]);
+            }
+            sequence
+        };
+        match tag.direction {
+            TagDirection::Forward => forward,
+            TagDirection::Reverse => backward,
+            TagDirection::PingPong => ping_pong(&forward, &backward),
+            TagDirection::PingPongReverse => ping_pong(&backward, &forward),
+        }
+    }
+
+    pub fn frame_durations(&self, tag: &AsepriteTag) -> Vec<f32> {
+        self.frame_sequence(tag)
+            .into_iter()
+            .map(|index| {
+                self.frames[index]
+                    .duration
+                    .unwrap_or(DEFAULT_FRAME_DURATION)
+            })
+            .collect()
+    }
+
+    pub fn animation(&self, image: &Rc<Image>, name: &str) -> Option<SpriteSheetAnimation> {
+        let tag = self.tag(name)?;
+        let frames: Vec<SpriteFrame> = self
+            .frame_sequence(tag)
+            .into_iter()
+            .map(|index| self.frames[index].frame)
+            .collect();
+        let spritesheet = SpriteSheet::from_frames(Rc::clone(image), frames.into());
+        Some(SpriteSheetAnimation::with_frame_durations(
+            spritesheet,
+            self.frame_durations(tag),
+        ))
+    }
+
+    pub fn animations(&self, image: &Rc<Image>) -> HashMap<String, SpriteSheetAnimation> {
+        self.tags
+            .iter()
+            .filter_map(|tag| Some((tag.name.clone(), self.animation(image, &tag.name)?)))
+            .collect()
+    }
+}
+
+pub fn load_animations<P: AsRef<Path>>(
+    ctx: &mut Context,
+    image_path: P,
+    json_path: P,
+) -> GameResult<HashMap<String, SpriteSheetAnimation>> {
+    let image = Rc::new(Image::new(ctx, image_path)?);
+    let sheet = AsepriteSheet::load(ctx, json_path)?;
+    Ok(sheet.animations(&image))
+}
+
+fn parse_tag(value: &Value) -> GameResult<AsepriteTag> {
+    let name = value
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| atlas_error("tag without name".to_string()))?;
+    let index = |key: &str| {
+        value
+            .get(key)
+            .and_then(Value::as_u64)
+            .map(|index| index as usize)
+            .ok_or_else(|| atlas_error(format!("tag {} has no {}", name, key)))
+    };
+    let direction = match value.get("direction").and_then(Value::as_str) {
+        None | Some("forward") => TagDirection::Forward,
+        Some("reverse") => TagDirection::Reverse,
+        Some("pingpong") => TagDirection::PingPong,
+        Some("pingpong_reverse") => TagDirection::PingPongReverse,
+        Some(other) => {
+            return Err(atlas_error(format!(
+                "tag {} has unknown direction {}",
+                name, other
+            )))
+        }
+    };
+    Ok(AsepriteTag {
+        name: name.to_string(),
+        from: index("from")?,
+        to: index("to")?,
+        direction,
+    })
+}
@@ -0,0 +1,956 @@
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
+
+use ggez::{
+    audio::{SoundData, Source},
+    filesystem,
+    graphics::{FilterMode, Image},
+    Context, GameError, GameResult,
+};
+
+use serde_json::Value;
+
+use crate::{atlas::read_to_string, rendering::SpriteSheet};
+
+const DEFAULT_POLL_INTERVAL: f32 = 0.5;
+const PLACEHOLDER_SIZE: u16 = 16;
+const PLACEHOLDER_CELL: u16 = 4;
+const PLACEHOLDER_COLORS: [[u8; 4]; 2] = [[255, 0, 255, 255], [0, 0, 0, 255]];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    Image,
+    Sound,
+    Level,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AssetHandle {
+    kind: AssetKind,
+    index: usize,
+}
+
+impl AssetHandle {
+    pub fn new(kind: AssetKind, index: usize) -> Self {
+        Self { kind, index }
+    }
+
+    pub fn kind(&self) -> AssetKind {
+        self.kind
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+pub trait Asset: Sized {
+    const KIND: AssetKind;
+
+    fn load(ctx: &mut Context, path: &str) -> GameResult<Self>;
+
+    fn storage(assets: &AssetManager) -> &AssetStorage<Self>;
+
+    fn storage_mut(assets: &mut AssetManager) -> &mut AssetStorage<Self>;
+}
+
+impl Asset for Image {
+    const KIND: AssetKind = AssetKind::Image;
+
+    fn load(ctx: &mut Context, path: &str) -> GameResult<Self> {
+        Image::new(ctx, path)
+    }
+
+    fn storage(assets: &AssetManager) -> &AssetStorage<Self> {
+        &assets.images
+    }
+
+    fn storage_mut(assets: &mut AssetManager) -> &mut AssetStorage<Self> {
+        &mut assets.images
+    }
+}
+
+impl Asset for SoundData {
+    const KIND: AssetKind = AssetKind::Sound;
+
+    fn load(ctx: &mut Context, path: &str) -> GameResult<Self> {
+        SoundData::new(ctx, path)
+    }
+
+    fn storage(assets: &AssetManager) -> &AssetStorage<Self> {
+        &assets.sounds
+    }
+
+    fn storage_mut(assets: &mut AssetManager) -> &mut AssetStorage<Self> {
+        &mut assets.sounds
+    }
+}
+
+impl Asset for Level {
+    const KIND: AssetKind = AssetKind::Level;
+
+    fn load(ctx: &mut Context, path: &str) -> GameResult<Self> {
+        Level::parse(&read_to_string(ctx, path)?)
+    }
+
+    fn storage(assets: &AssetManager) -> &AssetStorage<Self> {
+        &assets.levels
+    }
+
+    fn storage_mut(assets: &mut AssetManager) -> &mut AssetStorage<Self> {
+        &mut assets.levels
+    }
+}
+
+pub struct Handle<T> {
+    index: usize,
+    token: Rc<()>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    fn new(index: usize, token: &Rc<()>) -> Self {
+        Self {
+            index,
+            token: Rc::clone(token),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T: Asset> Handle<T> {
+    pub fn untyped(&self) -> AssetHandle {
+        AssetHandle::new(T::KIND, self.index)
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.index, &self.token)
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Handle({})", self.index)
+    }
+}
+
+impl<T: Asset> PartialEq<AssetHandle> for Handle<T> {
+    fn eq(&self, other: &AssetHandle) -> bool {
+        self.untyped() == *other
+    }
+}
+
+impl<T: Asset> From<&Handle<T>> for AssetHandle {
+    fn from(handle: &Handle<T>) -> Self {
+        handle.untyped()
+    }
+}
+
+struct Slot<T> {
+    path: String,
+    asset: Rc<T>,
+    token: Rc<()>,
+    fallback: bool,
+}
+
+impl<T> Slot<T> {
+    fn is_unused(&self) -> bool {
+        Rc::strong_count(&self.token) == 1
+    }
+}
+
+pub struct AssetStorage<T> {
+    slots: Vec<Option<Slot<T>>>,
+    paths: HashMap<String, usize>,
+}
+
+impl<T> Default for AssetStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> AssetStorage<T> {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            paths: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, path: &str, asset: Rc<T>, fallback: bool) -> Handle<T> {
+        if let Some(handle) = self.find(path) {
+            return handle;
+        }
+        let token = Rc::new(());
+        let handle = Handle::new(self.slots.len(), &token);
+        self.slots.push(Some(Slot {
+            path: path.to_string(),
+            asset,
+            token,
+            fallback,
+        }));
+        self.paths.insert(path.to_string(), handle.index);
+        handle
+    }
+
+    pub fn find(&self, path: &str) -> Option<Handle<T>> {
+        let index = *self.paths.get(path)?;
+        self.slot(index).map(|slot| Handle::new(index, &slot.token))
+    }
+
+    pub fn get(&self, handle: &Handle<T>) -> Option<&Rc<T>> {
+        self.slot(handle.index).map(|slot| &slot.asset)
+    }
+
+    pub fn path(&self, handle: &Handle<T>) -> Option<&str> {
+        self.path_at(handle.index)
+    }
+
+    pub fn ref_count(&self, handle: &Handle<T>) -> usize {
+        self.slot(handle.index)
+            .map_or(0, |slot| Rc::strong_count(&slot.token) - 1)
+    }
+
+    pub fn is_fallback(&self, handle: &Handle<T>) -> bool {
+        self.slot(handle.index).is_some_and(|slot| slot.fallback)
+    }
+
+    pub fn collect(&mut self) -> Vec<usize> {
+        (0..self.slots.len())
+            .filter(|index| self.free_if_unused(*index))
+            .collect()
+    }
+
+    pub fn replace(&mut self, handle: &Handle<T>, asset: Rc<T>) -> bool {
+        self.replace_at(handle.index, asset)
+    }
+
+    pub fn modify<F>(&mut self, handle: &Handle<T>, modify: F) -> bool
+    where
+        F: FnOnce(&T) -> T,
+    {
+        match self.slot_mut(handle.index) {
+            Some(slot) => {
+                slot.asset = Rc::new(modify(&slot.asset));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    fn path_at(&self, index: usize) -> Option<&str> {
+        self.slot(index).map(|slot| slot.path.as_str())
+    }
+
+    fn replace_at(&mut self, index: usize, asset: Rc<T>) -> bool {
+        match self.slot_mut(index) {
+            Some(slot) => {
+                slot.asset = asset;
+                slot.fallback = false;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn free_if_unused(&mut self, index: usize) -> bool {
+        if !self.slot(index).is_some_and(Slot::is_unused) {
+            return false;
+        }
+        if let Some(slot) = self.slots[index].take() {
+            self.paths.remove(&slot.path);
+        }
+        true
+    }
+
+    fn slot(&self, index: usize) -> Option<&Slot<T>> {
+        self.slots.get(index).and_then(Option::as_ref)
+    }
+
+    fn slot_mut(&mut self, index: usize) -> Option<&mut Slot<T>> {
+        self.slots.get_mut(index).and_then(Option::as_mut)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum LoadedAsset {
+    Image(Handle<Image>),
+    Sound(Handle<SoundData>),
+    Level(Handle<Level>),
+}
+
+impl LoadedAsset {
+    pub fn handle(&self) -> AssetHandle {
+        match self {
+            LoadedAsset::Image(handle) => handle.untyped(),
+            LoadedAsset::Sound(handle) => handle.untyped(),
+            LoadedAsset::Level(handle) => handle.untyped(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AssetEvent {
+    Reloaded(AssetHandle),
+    Failed(AssetHandle, GameError),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    tiles: Vec<Vec<u32>>,
+}
+
+impl Level {
+    pub fn parse(text: &str) -> GameResult<Self> {
+        let mut tiles: Vec<Vec<u32>> = Vec::new();
+        for (line_index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let row = line
+                .split_whitespace()
+                .map(|tile| {
+                    tile.parse::<u32>().map_err(|_| {
+                        level_error(format!("invalid tile {} on line {}", tile, line_index + 1))
+                    })
+                })
+                .collect::<GameResult<Vec<u32>>>()?;
+            if let Some(first) = tiles.first() {
+                if first.len() != row.len() {
+                    return Err(level_error(format!(
+                        "line {} has {} tiles, expected {}",
+                        line_index + 1,
+                        row.len(),
+                        first.len()
+                    )));
+                }
+            }
+            tiles.push(row);
+        }
+        if tiles.is_empty() {
+            return Err(level_error("level has no tiles".to_string()));
+        }
+        Ok(Self { tiles })
+    }
+
+    pub fn tiles(&self) -> &Vec<Vec<u32>> {
+        &self.tiles
+    }
+
+    pub fn template(&self) -> Vec<&[u32]> {
+        self.tiles.iter().map(|row| &row[..]).collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.tiles.first().map_or(0, Vec::len)
+    }
+
+    pub fn height(&self) -> usize {
+        self.tiles.len()
+    }
+}
+
+fn level_error(message: String) -> GameError {
+    GameError::ResourceLoadError(format!("Failed to parse level: {}", message))
+}
+
+struct WatchedFile<K> {
+    key: K,
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+}
+
+pub struct FileWatcher<K = AssetHandle> {
+    files: Vec<WatchedFile<K>>,
+    poll_interval: f32,
+    elapsed: f32,
+}
+
+impl<K: Copy + PartialEq> Default for FileWatcher<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Copy + PartialEq> FileWatcher<K> {
+    pub fn new() -> Self {
+        Self {
+            files: Vec::new(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn set_poll_interval(&mut self, poll_interval: Duration) {
+        self.poll_interval = poll_interval.as_secs_f32();
+    }
+
+    pub fn watch<P: Into<PathBuf>>(&mut self, key: K, path: P) {
+        let path = path.into();
+        let last_modified = modified(&path);
+        match self.files.iter_mut().find(|file| file.key == key) {
+            Some(file) => {
+                file.path = path;
+                file.last_modified = last_modified;
+            }
+            None => self.files.push(WatchedFile {
+                key,
+                path,
+                last_modified,
+            }),
+        }
+    }
+
+    pub fn unwatch(&mut self, key: K) {
+        self.files.retain(|file| file.key != key);
+    }
+
+    pub fn invalidate(&mut self, key: K) {
+        if let Some(file) = self.files.iter_mut().find(|file| file.key == key) {
+            file.last_modified = None;
+        }
+    }
+
+    pub fn is_watching(&self, key: K) -> bool {
+        self.files.iter().any(|file| file.key == key)
+    }
+
+    pub fn path(&self, key: K) -> Option<&Path> {
+        self.files
+            .iter()
+            .find(|file| file.key == key)
+            .map(|file| file.path.as_path())
+    }
+
+    pub fn poll(&mut self, deltatime: Duration) -> Vec<K> {
+        self.elapsed += deltatime.as_secs_f32();
+        if self.elapsed < self.poll_interval {
+            return Vec::new();
+        }
+        self.elapsed = 0.0;
+        self.changed()
+    }
+
+    pub fn changed(&mut self) -> Vec<K> {
+        let mut changed = Vec::new();
+        for file in self.files.iter_mut() {
+            let last_modified = modified(&file.path);
+            if last_modified.is_some() && last_modified != file.last_modified {
+                file.last_modified = last_modified;
+                changed.push(file.key);
+            }
+        }
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+pub fn resolve_on_disk<P: AsRef<Path>>(roots: &[PathBuf], path: P) -> Option<PathBuf> {
+    let relative = relative_path(path.as_ref());
+    roots
+        .iter()
+        .map(|root| root.join(relative))
+        .find(|candidate| candidate.is_file())
+}
+
+fn relative_path(path: &Path) -> &Path {
+    path.strip_prefix("/").unwrap_or(path)
+}
+
+pub fn placeholder_pixels(size: u16, cell: u16) -> Vec<u8> {
+    let cell = cell.max(1);
+    let mut pixels = Vec::with_capacity(size as usize * size as usize * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let color = PLACEHOLDER_COLORS[((x / cell + y / cell) % 2) as usize];
+            pixels.extend_from_slice(&color);
+        }
+    }
+    pixels
+}
+
+#[derive(Debug)]
+pub struct AssetError {
+    pub kind: AssetKind,
+    pub path: String,
+    pub error: GameError,
+}
+
+impl AssetError {
+    pub fn new(kind: AssetKind, path: &str, error: GameError) -> Self {
+        Self {
+            kind,
+            path: path.to_string(),
+            error,
+        }
+    }
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Failed to load {:?} {}: {}",
+            self.kind, self.path, self.error
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadProgress {
+    pub path: String,
+    pub loaded: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl LoadProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.loaded as f32 / self.total as f32
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.loaded >= self.total
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssetManifest {
+    entries: Vec<(AssetKind, String)>,
+}
+
+impl AssetManifest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn image(mut self, path: &str) -> Self {
+        self.entries.push((AssetKind::Image, path.to_string()));
+        self
+    }
+
+    pub fn sound(mut self, path: &str) -> Self {
+        self.entries.push((AssetKind::Sound, path.to_string()));
+        self
+    }
+
+    pub fn level(mut self, path: &str) -> Self {
+        self.entries.push((AssetKind::Level, path.to_string()));
+        self
+    }
+
+    pub fn from_json(json: &str) -> GameResult<Self> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|err| manifest_error(err.to_string()))?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| manifest_error("expected an object".to_string()))?;
+        let mut manifest = Self::new();
+        for (key, paths) in object.iter() {
+            let kind = match key.as_str() {
+                "images" => AssetKind::Image,
+                "sounds" => AssetKind::Sound,
+                "levels" => AssetKind::Level,
+                _ => return Err(manifest_error(format!("unknown section {}", key))),
+            };
+            let paths = paths
+                .as_array()
+                .ok_or_else(|| manifest_error(format!("{} is not an array", key)))?;
+            for path in paths.iter() {
+                let path = path
+                    .as_str()
+                    .ok_or_else(|| manifest_error(format!("{} contains a non-string path", key)))?;
+                manifest.entries.push((kind, path.to_string()));
+            }
+        }
+        Ok(manifest)
+    }
+
+    pub fn load(ctx: &mut Context, path: &str) -> GameResult<Self> {
+        Self::from_json(&read_to_string(ctx, path)?)
+    }
+
+    pub fn entries(&self) -> &[(AssetKind, String)] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn manifest_error(message: String) -> GameError {
+    GameError::ResourceLoadError(format!("Failed to parse asset manifest: {}", message))
+}
+
+pub struct AssetManager {
+    images: AssetStorage<Image>,
+    sounds: AssetStorage<SoundData>,
+    levels: AssetStorage<Level>,
+    filters: HashMap<usize, FilterMode>,
+    placeholder: Option<Rc<Image>>,
+    errors: Vec<AssetError>,
+    roots: Vec<PathBuf>,
+    watcher: FileWatcher,
+}
+
+impl AssetManager {
+    pub fn new(ctx: &Context) -> Self {
+        Self::with_roots(vec![
+            filesystem::resources_dir(ctx).to_path_buf(),
+            filesystem::user_data_dir(ctx).to_path_buf(),
+            filesystem::user_config_dir(ctx).to_path_buf(),
+        ])
+    }
+
+    pub fn with_roots(roots: Vec<PathBuf>) -> Self {
+        Self {
+            images: AssetStorage::new(),
+            sounds: AssetStorage::new(),
+            levels: AssetStorage::new(),
+            filters: HashMap::new(),
+            placeholder: None,
+            errors: Vec::new(),
+            roots,
+            watcher: FileWatcher::new(),
+        }
+    }
+
+    pub fn set_poll_interval(&mut self, poll_interval: Duration) {
+        self.watcher.set_poll_interval(poll_interval);
+    }
+
+    pub fn watcher(&self) -> &FileWatcher {
+        &self.watcher
+    }
+
+    pub fn storage<T: Asset>(&self) -> &AssetStorage<T> {
+        T::storage(self)
+    }
+
+    pub fn load<T: Asset>(&mut self, ctx: &mut Context, path: &str) -> GameResult<Handle<T>> {
+        if let Some(handle) = T::storage(self).find(path) {
+            return Ok(handle);
+        }
+        let asset = T::load(ctx, path)?;
+        let handle = T::storage_mut(self).insert(path, Rc::new(asset), false);
+        self.watch(handle.untyped(), path);
+        Ok(handle)
+    }
+
+    pub fn try_load<T: Asset>(&mut self, ctx: &mut Context, path: &str) -> Option<Handle<T>> {
+        match self.load(ctx, path) {
+            Ok(handle) => Some(handle),
+            Err(error) => {
+                self.report(T::KIND, path, error);
+                None
+            }
+        }
+    }
+
+    pub fn load_image(&mut self, ctx: &mut Context, path: &str) -> GameResult<Handle<Image>> {
+        self.load(ctx, path)
+    }
+
+    pub fn load_sound(&mut self, ctx: &mut Context, path: &str) -> GameResult<Handle<SoundData>> {
+        self.load(ctx, path)
+    }
+
+    pub fn load_level(&mut self, ctx: &mut Context, path: &str) -> GameResult<Handle<Level>> {
+        self.load(ctx, path)
+    }
+
+    pub fn load_image_or_fallback(
+        &mut self,
+        ctx: &mut Context,
+        path: &str,
+    ) -> GameResult<Handle<Image>> {
+        let error = match self.load_image(ctx, path) {
+            Ok(handle) => return Ok(handle),
+            Err(error) => error,
+        };
+        self.report(AssetKind::Image, path, error);
+        let placeholder = self.placeholder(ctx)?;
+        let handle = self.images.insert(path, placeholder, true);
+        self.watch(handle.untyped(), path);
+        Ok(handle)
+    }
+
+    pub fn load_manifest<F>(
+        &mut self,
+        ctx: &mut Context,
+        manifest: &AssetManifest,
+        mut on_progress: F,
+    ) -> GameResult<Vec<LoadedAsset>>
+    where
+        F: FnMut(&mut Context, &LoadProgress) -> GameResult,
+    {
+        let mut handles = Vec::with_capacity(manifest.len());
+        let mut progress = LoadProgress {
+            path: String::new(),
+            loaded: 0,
+            failed: 0,
+            total: manifest.len(),
+        };
+        for (kind, path) in manifest.entries().iter() {
+            let (handle, failed) = match kind {
+                AssetKind::Image => match self.load_image_or_fallback(ctx, path) {
+                    Ok(handle) => {
+                        let failed = self.is_fallback(&handle);
+                        (Some(LoadedAsset::Image(handle)), failed)
+                    }
+                    Err(error) => {
+                        self.report(AssetKind::Image, path, error);
+                        (None, true)
+                    }
+                },
+                AssetKind::Sound => {
+                    let handle = self.try_load(ctx, path).map(LoadedAsset::Sound);
+                    let failed = handle.is_none();
+                    (handle, failed)
+                }
+                AssetKind::Level => {
+                    let handle = self.try_load(ctx, path).map(LoadedAsset::Level);
+                    let failed = handle.is_none();
+                    (handle, failed)
+                }
+            };
+            handles.extend(handle);
+            progress.path = path.clone();
+            progress.loaded += 1;
+            if failed {
+                progress.failed += 1;
+            }
+            on_progress(ctx, &progress)?;
+        }
+        Ok(handles)
+    }
+
+    pub fn placeholder(&mut self, ctx: &mut Context) -> GameResult<Rc<Image>> {
+        if let Some(placeholder) = &self.placeholder {
+            return Ok(Rc::clone(placeholder));
+        }
+        let mut image = Image::from_rgba8(
+            ctx,
+            PLACEHOLDER_SIZE,
+            PLACEHOLDER_SIZE,
+            &placeholder_pixels(PLACEHOLDER_SIZE, PLACEHOLDER_CELL),
+        )?;
+        image.set_filter(FilterMode::Nearest);
+        let placeholder = Rc::new(image);
+        self.placeholder = Some(Rc::clone(&placeholder));
+        Ok(placeholder)
+    }
+
+    pub fn ref_count<T: Asset>(&self, handle: &Handle<T>) -> usize {
+        T::storage(self).ref_count(handle)
+    }
+
+    pub fn errors(&self) -> &[AssetError] {
+        &self.errors
+    }
+
+    pub fn take_errors(&mut self) -> Vec<AssetError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    pub fn path(&self, handle: AssetHandle) -> Option<&str> {
+        match handle.kind {
+            AssetKind::Image => self.images.path_at(handle.index),
+            AssetKind::Sound => self.sounds.path_at(handle.index),
+            AssetKind::Level => self.levels.path_at(handle.index),
+        }
+    }
+
+    pub fn get<T: Asset>(&self, handle: &Handle<T>) -> Option<Rc<T>> {
+        T::storage(self).get(handle).map(Rc::clone)
+    }
+
+    pub fn image(&self, handle: &Handle<Image>) -> Option<Rc<Image>> {
+        self.get(handle)
+    }
+
+    pub fn is_fallback(&self, handle: &Handle<Image>) -> bool {
+        self.images.is_fallback(handle)
+    }
+
+    pub fn set_filter(&mut self, handle: &Handle<Image>, filter: FilterMode) {
+        let filtered = self.images.modify(handle, |image| {
+            let mut image = image.clone();
+            image.set_filter(filter);
+            image
+        });
+        if filtered {
+            self.filters.insert(handle.index(), filter);
+        }
+    }
+
+    pub fn sprite_sheet(
+        &self,
+        handle: &Handle<Image>,
+        rows: u32,
+        cols: u32,
+        total_sprites: u32,
+    ) -> GameResult<SpriteSheet> {
+        match self.image(handle) {
+            Some(image) => Ok(SpriteSheet::from_grid(image, rows, cols, total_sprites)),
+            None => Err(unknown_handle(handle.untyped())),
+        }
+    }
+
+    pub fn sound_data(&self, handle: &Handle<SoundData>) -> Option<SoundData> {
+        self.sounds.get(handle).map(|data| (**data).clone())
+    }
+
+    pub fn source(&self, ctx: &mut Context, handle: &Handle<SoundData>) -> GameResult<Source> {
+        match self.sound_data(handle) {
+            Some(data) => Source::from_data(ctx, data),
+            None => Err(unknown_handle(handle.untyped())),
+        }
+    }
+
+    pub fn level(&self, handle: &Handle<Level>) -> Option<&Level> {
+        self.levels.get(handle).map(|level| level.as_ref())
+    }
+
+    pub fn update(&mut self, ctx: &mut Context, deltatime: Duration) -> Vec<AssetEvent> {
+        self.collect_unused();
+        let changed = self.watcher.poll(deltatime);
+        self.reload_all(ctx, &changed)
+    }
+
+    pub fn reload_changed(&mut self, ctx: &mut Context) -> Vec<AssetEvent> {
+        let changed = self.watcher.changed();
+        self.reload_all(ctx, &changed)
+    }
+
+    pub fn reload(&mut self, ctx: &mut Context, handle: AssetHandle) -> GameResult {
+        let path = match self.path(handle) {
+            Some(path) => path.to_string(),
+            None => return Err(unknown_handle(handle)),
+        };
+        match handle.kind {
+            AssetKind::Image => {
+                let mut image = Image::load(ctx, &path)?;
+                if let Some(filter) = self.filters.get(&handle.index) {
+                    image.set_filter(*filter);
+                }
+                self.images.replace_at(handle.index, Rc::new(image));
+            }
+            AssetKind::Sound => self.reload_typed::<SoundData>(ctx, handle.index, &path)?,
+            AssetKind::Level => self.reload_typed::<Level>(ctx, handle.index, &path)?,
+        }
+        Ok(())
+    }
+
+    fn reload_typed<T: Asset>(
+        &mut self,
+        ctx: &mut Context,
+        index: usize,
+        path: &str,
+    ) -> GameResult {
+        let asset = T::load(ctx, path)?;
+        T::storage_mut(self).replace_at(index, Rc::new(asset));
+        Ok(())
+    }
+
+    pub fn collect_unused(&mut self) {
+        for index in self.images.collect() {
+            self.watcher
+                .unwatch(AssetHandle::new(AssetKind::Image, index));
+            self.filters.remove(&index);
+        }
+        for index in self.sounds.collect() {
+            self.watcher
+                .unwatch(AssetHandle::new(AssetKind::Sound, index));
+        }
+        for index in self.levels.collect() {
+            self.watcher
+                .unwatch(AssetHandle::new(AssetKind::Level, index));
+        }
+    }
+
+    fn reload_all(&mut self, ctx: &mut Context, handles: &[AssetHandle]) -> Vec<AssetEvent> {
+        handles
+            .iter()
+            .map(|handle| match self.reload(ctx, *handle) {
+                Ok(()) => AssetEvent::Reloaded(*handle),
+                Err(err) => AssetEvent::Failed(*handle, err),
+            })
+            .collect()
+    }
+
+    fn report(&mut self, kind: AssetKind, path: &str, error: GameError) {
+        let reported = self
+            .errors
+            .iter()
+            .any(|reported| reported.kind == kind && reported.path == path);
+        if !reported {
+            self.errors.push(AssetError::new(kind, path, error));
+        }
+    }
+
+    fn watch(&mut self, handle: AssetHandle, path: &str) {
+        let disk_path = resolve_on_disk(&self.roots, path).or_else(|| {
+            self.roots
+                .first()
+                .map(|root| root.join(relative_path(Path::new(path))))
+        });
+        if let Some(disk_path) = disk_path {
+            self.watcher.watch(handle, disk_path);
+        }
+    }
+}
+
+fn unknown_handle(handle: AssetHandle) -> GameError {
+    GameError::ResourceLoadError(format!("Unknown asset handle {:?}", handle))
+}
@@ -0,0 +1,196 @@
+use std::{collections::HashMap, io::Read, path::Path, rc::Rc};
+
+use ggez::{
+    filesystem,
+    graphics::{Image, Rect},
+    mint::Point2,
+    Context, GameError, GameResult,
+};
+use serde_json::{Map, Value};
+
+use crate::rendering::{SpriteFrame, SpriteSheet};
+
+pub struct AtlasFrame {
+    pub name: String,
+    pub frame: SpriteFrame,
+    pub duration: Option<f32>,
+}
+
+pub struct TextureAtlas {
+    image: Rc<Image>,
+    frames: Vec<SpriteFrame>,
+    names: HashMap<String, usize>,
+}
+
+impl TextureAtlas {
+    pub fn from_frames(image: Rc<Image>, atlas_frames: Vec<AtlasFrame>) -> Self {
+        let mut frames = Vec::with_capacity(atlas_frames.len());
+        let mut names = HashMap::new();
+        for (index, atlas_frame) in atlas_frames.into_iter().enumerate() {
+            names.insert(atlas_frame.name, index);
+            frames.push(atlas_frame.frame);
+        }
+        Self {
+            image,
+            frames,
+            names,
+        }
+    }
+
+    pub fn from_json(image: Rc<Image>, json: &str) -> GameResult<Self> {
+        Ok(Self::from_frames(image, parse_atlas_json(json)?))
+    }
+
+    pub fn load<P: AsRef<Path>>(
+        ctx: &mut Context,
+        image_path: P,
+        json_path: P,
+    ) -> GameResult<Self> {
+        let image = Image::new(ctx, image_path)?;
+        let json = read_to_string(ctx, json_path)?;
+        Self::from_json(Rc::new(image), &json)
+    }
+
+    pub fn sheet(&self) -> SpriteSheet {
+        SpriteSheet::from_frames(Rc::clone(&self.image), self.frames.clone().into())
+    }
+
+    pub fn sheet_from_names(&self, names: &[&str]) -> GameResult<SpriteSheet> {
+        let frames = names
+            .iter()
+            .map(|name| {
+                self.frame_by_name(name)
+                    .copied()
+                    .ok_or_else(|| atlas_error(format!("missing frame {}", name)))
+            })
+            .collect::<GameResult<Vec<_>>>()?;
+        Ok(SpriteSheet::from_frames(
+            Rc::clone(&self.image),
+            frames.into(),
+        ))
+    }
+
+    pub fn sheet_from_range(&self, from: usize, to: usize) -> GameResult<SpriteSheet> {
+        let frames = self
+            .frames
+            .get(from..=to)
+            .ok_or_else(|| atlas_error(format!("frame range {}..={} out of bounds", from, to)))?;
+        Ok(SpriteSheet::from_frames(
+            Rc::clone(&self.image),
+            frames.into(),
+        ))
+    }
+
+    pub fn frame_by_name(&self, name: &str) -> Option<&SpriteFrame> {
+        self.names.get(name).map(|index| &self.frames[*index])
+    }
+
+    pub fn frames(&self) -> &[SpriteFrame] {
+        &self.frames
+    }
+
+    pub fn image(&self) -> &Rc<Image> {
+        &self.image
+    }
+}
+
+pub fn parse_atlas_json(json: &str) -> GameResult<Vec<AtlasFrame>> {
+    let root: Value =
+        serde_json::from_str(json).map_err(|err| atlas_error(format!("invalid json: {}", err)))?;
+    match root.get("frames") {
+        Some(Value::Object(frames)) => frames
+            .iter()
+            .map(|(name, value)| parse_frame(name, value))
+            .collect(),
+        Some(Value::Array(frames)) => frames
+            .iter()
+            .map(|value| {
+                let name = value
+                    .get("filename")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| atlas_error("frame without filename".to_string()))?;
+                parse_frame(name, value)
+            })
+            .collect(),
+        _ => Err(atlas_error("missing frames".to_string())),
+    }
+}
+
+pub(crate) fn read_to_string<P: AsRef<Path>>(ctx: &mut Context, path: P) -> GameResult<String> {
+    let mut file = filesystem::open(ctx, path)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+pub(crate) fn atlas_error(message: String) -> GameError {
+    GameError::ResourceLoadError(format!("Failed to parse atlas: {}", message))
+}
+
+fn parse_frame(name: &str, value: &Value) -> GameResult<AtlasFrame> {
+    if value.get("rotated").and_then(Value::as_bool) == Some(true) {
+        return Err(atlas_error(format!(
+            "rotated frame {} is not supported",
+            name
+        )));
+    }
+    let rect = value
+        .get("frame")
+        .and_then(Value::as_object)
+        .and_then(parse_rect)
+        .ok_or_else(|| atlas_error(format!("frame {} has no valid rect", name)))?;
+
+    let mut pivot = value
+        .get("pivot")
+        .and_then(Value::as_object)
+        .and_then(|pivot| {
+            Some(Point2 {
+                x: field(pivot, "x")?,
+                y: field(pivot, "y")?,
+            })
+        })
+        .unwrap_or(Point2 { x: 0.5, y: 0.5 });
+
+    let source_size = value.get("sourceSize").and_then(Value::as_object);
+    let sprite_source = value.get("spriteSourceSize").and_then(Value::as_object);
+    if let (Some(source_size), Some(sprite_source)) = (source_size, sprite_source) {
+        if let (Some(source_w), Some(source_h), Some(offset_x), Some(offset_y)) = (
+            field(source_size, "w"),
+            field(source_size, "h"),
+            field(sprite_source, "x"),
+            field(sprite_source, "y"),
+        ) {
+            if rect.w > 0.0 && rect.h > 0.0 {
+                pivot = Point2 {
+                    x: (pivot.x * source_w - offset_x) / rect.w,
+                    y: (pivot.y * source_h - offset_y) / rect.h,
+                };
+            }
+        }
+    }
+
+    Ok(AtlasFrame {
+        name: name.to_string(),
+        frame: SpriteFrame::with_pivot(rect, pivot),
+        duration: value
+            .get("duration")
+            .and_then(Value::as_f64)
+            .map(|millis| millis as f32 / 1000.0),
+    })
+}
+
+fn parse_rect(object: &Map<String, Value>) -> Option<Rect> {
+    Some(Rect::new(
+        field(object, "x")?,
+        field(object, "y")?,
+        field(object, "w")?,
+        field(object, "h")?,
+    ))
+}
+
+fn field(object: &Map<String, Value>, key: &str) -> Option<f32> {
+    object
+        .get(key)
+        .and_then(Value::as_f64)
+        .map(|value| value as f32)
+}
@@ -1,7 +1,13 @@
 use std::time::Duration;
 
-use ggez::mint::Point2;
+use ggez::{
+    graphics::Rect,
+    mint::{Point2, Vector2},
+};
 
+use crate::{collision::DynamicCollider, world::World};
+
+#[derive(Clone, Copy)]
 pub enum FollowDirection {
     Horizontal,
     Vertical,
@@ -62,6 +68,7 @@ pub struct SmoothCamera {
     destination: Point2<f32>,
     follow_direction: FollowDirection,
     smoothness: f32,
+    dead_zone: Vector2<f32>,
 }
 
 impl SmoothCamera {
@@ -71,8 +78,33 @@ impl SmoothCamera {
             destination: position,
             follow_direction: FollowDirection::Both,
             smoothness,
+            dead_zone: Vector2 { x: 0.0, y: 0.0 },
         }
     }
+
+    pub fn set_dead_zone(&mut self, dead_zone: Vector2<f32>) {
+        self.dead_zone = Vector2 {
+            x: dead_zone.x.max(0.0),
+            y: dead_zone.y.max(0.0),
+        };
+    }
+
+    pub fn dead_zone(&self) -> Vector2<f32> {
+        self.dead_zone
+    }
+
+    pub fn dead_zone_rect(&self) -> Rect {
+        Rect::new(
+            self.position.x,
+            self.position.y,
+            2.0 * self.dead_zone.x,
+            2.0 * self.dead_zone.y,
+        )
+    }
+
+    pub fn destination(&self) -> Point2<f32> {
+        self.destination
+    }
 }
 
 impl Camera for SmoothCamera {
@@ -86,9 +118,15 @@ impl Camera for SmoothCamera {
 
     fn update(&mut self, deltatime: Duration) {
         let seconds = deltatime.as_secs_f32();
+        let outside_dead_zone =
+            |offset: f32, dead_zone: f32| offset.signum() * (offset.abs() - dead_zone).max(0.0);
+        let offset = Vector2 {
+            x: outside_dead_zone(self.destination.x - self.position.x, self.dead_zone.x),
+            y: outside_dead_zone(self.destination.y - self.position.y, self.dead_zone.y),
+        };
         let mut new_position = Point2 {
-            x: self.position.x + (self.destination.x - self.position.x) * self.smoothness * seconds,
-            y: self.position.y + (self.destination.y - self.position.y) * self.smoothness * seconds,
+            x: self.position.x + offset.x * self.smoothness * seconds,
+            y: self.position.y + offset.y * self.smoothness * seconds,
         };
         match self.follow_direction {
             FollowDirection::Horizontal => new_position.y = self.position.y,
@@ -102,3 +140,385 @@ impl Camera for SmoothCamera {
         self.position
     }
 }
+
+pub struct CameraCrossFade {
+    from: Box<dyn Camera>,
+    to: Box<dyn Camera>,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl CameraCrossFade {
+    pub fn new(from: Box<dyn Camera>, to: Box<dyn Camera>, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            duration: duration.as_secs_f32(),
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        smoothstep((self.elapsed / self.duration).min(1.0))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    pub fn into_target(self) -> Box<dyn Camera> {
+        self.to
+    }
+}
+
+impl Camera for CameraCrossFade {
+    fn set_destination(&mut self, point: Point2<f32>) {
+        self.from.set_destination(point);
+        self.to.set_destination(point);
+    }
+
+    fn set_follow_direction(&mut self, follow_direction: FollowDirection) {
+        self.from.set_follow_direction(follow_direction);
+        self.to.set_follow_direction(follow_direction);
+    }
+
+    fn update(&mut self, deltatime: Duration) {
+        self.from.update(deltatime);
+        self.to.update(deltatime);
+        self.elapsed = (self.elapsed + deltatime.as_secs_f32()).min(self.duration);
+    }
+
+    fn position(&self) -> Point2<f32> {
+        lerp_point(self.from.position(), self.to.position(), self.progress())
+    }
+}
+
+pub struct ScreenShake {
+    trauma: f32,
+    decay: f32,
+    max_offset: Vector2<f32>,
+    frequency: f32,
+    time: f32,
+    seed: u32,
+}
+
+impl ScreenShake {
+    pub fn new(max_offset: Vector2<f32>, frequency: f32, decay: f32) -> Self {
+        Self {
+            trauma: 0.0,
+            decay,
+            max_offset,
+            frequency,
+            time: 0.0,
+            seed: 0,
+        }
+    }
+
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+    }
+
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    pub fn update(&mut self, deltatime: Duration) {
+        let seconds = deltatime.as_secs_f32();
+        self.time += seconds;
+        self.trauma = (self.trauma - self.decay * seconds).max(0.0);
+    }
+
+    pub fn offset(&self) -> Vector2<f32> {
+        let shake = self.trauma * self.trauma;
+        let sample = self.time * self.frequency;
+        Vector2 {
+            x: self.max_offset.x * shake * value_noise(self.seed, sample),
+            y: self.max_offset.y * shake * value_noise(self.seed.wrapping_add(1), sample),
+        }
+    }
+}
+
+pub struct ZoomTween {
+    from: f32,
+    to: f32,
+    duration: f32,
+    hold: Option<f32>,
+    elapsed: f32,
+}
+
+impl ZoomTween {
+    pub fn new(from: f32, to: f32, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            duration: duration.as_secs_f32(),
+            hold: None,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn timed(from: f32, to: f32, duration: Duration, hold: Duration) -> Self {
+        Self {
+            hold: Some(hold.as_secs_f32()),
+            ..Self::new(from, to, duration)
+        }
+    }
+
+    pub fn update(&mut self, deltatime: Duration) {
+        self.elapsed = (self.elapsed + deltatime.as_secs_f32()).min(self.total_duration());
+    }
+
+    pub fn distance(&self) -> f32 {
+        let progress = match self.hold {
+            Some(hold) => round_trip_progress(self.elapsed, self.duration, hold),
+            None => one_way_progress(self.elapsed, self.duration),
+        };
+        self.from + (self.to - self.from) * progress
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.total_duration()
+    }
+
+    fn total_duration(&self) -> f32 {
+        match self.hold {
+            Some(hold) => 2.0 * self.duration + hold,
+            None => self.duration,
+        }
+    }
+}
+
+pub struct CameraPan {
+    target: Point2<f32>,
+    travel: f32,
+    hold: f32,
+    elapsed: f32,
+}
+
+impl CameraPan {
+    pub fn new(target: Point2<f32>, travel: Duration, hold: Duration) -> Self {
+        Self {
+            target,
+            travel: travel.as_secs_f32(),
+            hold: hold.as_secs_f32(),
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, deltatime: Duration) {
+        self.elapsed = (self.elapsed + deltatime.as_secs_f32()).min(2.0 * self.travel + self.hold);
+    }
+
+    pub fn position(&self, origin: Point2<f32>) -> Point2<f32> {
+        lerp_point(
+            origin,
+            self.target,
+            round_trip_progress(self.elapsed, self.travel, self.hold),
+        )
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= 2.0 * self.travel + self.hold
+    }
+}
+
+pub struct CameraEffects {
+    shake: ScreenShake,
+    zoom: Option<ZoomTween>,
+    pan: Option<CameraPan>,
+}
+
+impl CameraEffects {
+    pub fn new(shake: ScreenShake) -> Self {
+        Self {
+            shake,
+            zoom: None,
+            pan: None,
+        }
+    }
+
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.shake.add_trauma(amount);
+    }
+
+    pub fn zoom(&mut self, tween: ZoomTween) {
+        self.zoom = Some(tween);
+    }
+
+    pub fn pan(&mut self, pan: CameraPan) {
+        self.pan = Some(pan);
+    }
+
+    pub fn shake(&self) -> &ScreenShake {
+        &self.shake
+    }
+
+    pub fn shake_mut(&mut self) -> &mut ScreenShake {
+        &mut self.shake
+    }
+
+    pub fn is_zooming(&self) -> bool {
+        self.zoom.is_some()
+    }
+
+    pub fn is_panning(&self) -> bool {
+        self.pan.is_some()
+    }
+
+    pub fn update(&mut self, deltatime: Duration) {
+        if self.zoom.as_ref().is_some_and(ZoomTween::is_finished) {
+            self.zoom = None;
+        }
+        if self.pan.as_ref().is_some_and(CameraPan::is_finished) {
+            self.pan = None;
+        }
+        self.shake.update(deltatime);
+        if let Some(zoom) = self.zoom.as_mut() {
+            zoom.update(deltatime);
+        }
+        if let Some(pan) = self.pan.as_mut() {
+            pan.update(deltatime);
+        }
+    }
+
+    pub fn position(&self, focus: Point2<f32>) -> Point2<f32> {
+        let position = match &self.pan {
+            Some(pan) => pan.position(focus),
+            None => focus,
+        };
+        let offset = self.shake.offset();
+        Point2 {
+            x: position.x + offset.x,
+            y: position.y + offset.y,
+        }
+    }
+
+    pub fn apply(&self, world: &mut World, focus: Point2<f32>) {
+        world.look_at(self.position(focus));
+        if let Some(zoom) = &self.zoom {
+            world.set_distance(zoom.distance());
+        }
+    }
+}
+
+pub struct GroupFraming {
+    padding: f32,
+    min_distance: f32,
+    max_distance: f32,
+}
+
+impl GroupFraming {
+    pub fn new(padding: f32, min_distance: f32, max_distance: f32) -> Self {
+        Self {
+            padding,
+            min_distance,
+            max_distance,
+        }
+    }
+
+    pub fn bounds(targets: &[&DynamicCollider]) -> Option<Rect> {
+        let mut rects = targets.iter().map(|target| target.rect());
+        let first = rects.next()?;
+        let (mut left, mut right, mut bottom, mut top) = rect_edges(first);
+        for rect in rects {
+            let edges = rect_edges(rect);
+            left = left.min(edges.0);
+            right = right.max(edges.1);
+            bottom = bottom.min(edges.2);
+            top = top.max(edges.3);
+        }
+        Some(Rect::new(
+            0.5 * (left + right),
+            0.5 * (bottom + top),
+            right - left,
+            top - bottom,
+        ))
+    }
+
+    pub fn distance(&self, bounds: Rect, world: &World) -> f32 {
+        let aspect = world.screen_width() / world.screen_height();
+        let half_w = 0.5 * bounds.w + self.padding;
+        let half_h = 0.5 * bounds.h + self.padding;
+        let distance = (half_w * (1.0 / aspect).min(1.0)).max(half_h * aspect.min(1.0));
+        distance.clamp(self.min_distance, self.max_distance)
+    }
+
+    pub fn frame(&self, targets: &[&DynamicCollider], camera: &mut dyn Camera, world: &mut World) {
+        if let Some(bounds) = Self::bounds(targets) {
+            camera.set_destination(bounds.point());
+            world.set_distance(self.distance(bounds, world));
+        }
+    }
+
+    pub fn padding(&self) -> f32 {
+        self.padding
+    }
+
+    pub fn min_distance(&self) -> f32 {
+        self.min_distance
+    }
+
+    pub fn max_distance(&self) -> f32 {
+        self.max_distance
+    }
+}
+
+fn rect_edges(rect: Rect) -> (f32, f32, f32, f32) {
+    (
+        rect.x - 0.5 * rect.w,
+        rect.x + 0.5 * rect.w,
+        rect.y - 0.5 * rect.h,
+        rect.y + 0.5 * rect.h,
+    )
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn one_way_progress(elapsed: f32, duration: f32) -> f32 {
+    if duration <= 0.0 {
+        return 1.0;
+    }
+    smoothstep((elapsed / duration).min(1.0))
+}
+
+fn round_trip_progress(elapsed: f32, travel: f32, hold: f32) -> f32 {
+    if elapsed < travel {
+        one_way_progress(elapsed, travel)
+    } else if elapsed < travel + hold {
+        1.0
+    } else {
+        1.0 - one_way_progress(elapsed - travel - hold, travel)
+    }
+}
+
+fn lerp_point(from: Point2<f32>, to: Point2<f32>, t: f32) -> Point2<f32> {
+    Point2 {
+        x: from.x + (to.x - from.x) * t,
+        y: from.y + (to.y - from.y) * t,
+    }
+}
+
+fn hash_noise(seed: u32, index: i32) -> f32 {
+    let mut n = (index as u32)
+        .wrapping_mul(374_761_393)
+        .wrapping_add(seed.wrapping_mul(668_265_263));
+    n = (n ^ (n >> 13)).wrapping_mul(1_274_126_177);
+    n ^= n >> 16;
+    2.0 * (n as f32 / u32::MAX as f32) - 1.0
+}
+
+fn value_noise(seed: u32, t: f32) -> f32 {
+    let index = t.floor();
+    let a = hash_noise(seed, index as i32);
+    let b = hash_noise(seed, index as i32 + 1);
+    a + (b - a) * smoothstep(t - index)
+}
@@ -144,6 +144,17 @@ impl TilemapCollider {
         result
     }
 
+    pub fn bounds(&self) -> Rect {
+        let rows = self.tiles.len() as i32;
+        let cols = self.tiles.iter().map(Vec::len).max().unwrap_or(0) as i32;
+        self.segment_to_world(&TilemapSegment {
+            left_bound: 0,
+            right_bound: cols - 1,
+            bottom_bound: 0,
+            top_bound: rows - 1,
+        })
+    }
+
     pub fn tiles_ref(&self) -> &Vec<Vec<bool>> {
         &self.tiles
     }
@@ -178,6 +189,12 @@ impl From<&TilemapRenderer> for TilemapCollider {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    pub point: Point2<f32>,
+    pub normal: Vector2<f32>,
+}
+
 pub struct DynamicCollider {
     position: Point2<f32>,
     width: f32,
@@ -237,35 +254,49 @@ impl DynamicCollider {
         Rect::new(self.position.x, self.position.y, self.width, self.height)
     }
 
-    pub fn resolve_collision(&mut self, rect: &Rect) {
+    pub fn resolve_collision(&mut self, rect: &Rect) -> Option<Contact> {
         let collision_magnitude_x = 0.5 * (rect.w + self.width) - (self.position.x - rect.x).abs();
         let collision_magnitude_y = 0.5 * (rect.h + self.height) - (self.position.y - rect.y).abs();
 
-        if collision_magnitude_x < collision_magnitude_y {
-            if collision_magnitude_x > self.width / 100.0 {
-                self.velocity.x = 0.0;
-                if self.position.x > rect.x {
-                    self.position.x = rect.x + 0.5 * rect.w + 0.5 * self.width;
-                } else {
-                    self.position.x = rect.x - 0.5 * rect.w - 0.5 * self.width;
-                }
+        let normal = if collision_magnitude_x < collision_magnitude_y {
+            if collision_magnitude_x <= self.width / 100.0 {
+                return None;
+            }
+            self.velocity.x = 0.0;
+            if self.position.x > rect.x {
+                self.position.x = rect.x + 0.5 * rect.w + 0.5 * self.width;
+                Vector2 { x: 1.0, y: 0.0 }
+            } else {
+                self.position.x = rect.x - 0.5 * rect.w - 0.5 * self.width;
+                Vector2 { x: -1.0, y: 0.0 }
             }
         } else {
-            if collision_magnitude_y > self.height / 100.0 {
-                self.velocity.y = 0.0;
-                if self.position.y > rect.y {
-                    self.position.y = rect.y + 0.5 * rect.h + 0.5 * self.height;
-                } else {
-                    self.position.y = rect.y - 0.5 * rect.h - 0.5 * self.height;
-                }
+            if collision_magnitude_y <= self.height / 100.0 {
+                return None;
             }
-        }
+            self.velocity.y = 0.0;
+            if self.position.y > rect.y {
+                self.position.y = rect.y + 0.5 * rect.h + 0.5 * self.height;
+                Vector2 { x: 0.0, y: 1.0 }
+            } else {
+                self.position.y = rect.y - 0.5 * rect.h - 0.5 * self.height;
+                Vector2 { x: 0.0, y: -1.0 }
+            }
+        };
+        Some(Contact {
+            point: Point2 {
+                x: self.position.x - normal.x * 0.5 * self.width,
+                y: self.position.y - normal.y * 0.5 * self.height,
+            },
+            normal,
+        })
     }
 
-    pub fn resolve_collisions(&mut self, walls: &[Rect]) {
-        for rect in walls {
-            self.resolve_collision(rect);
-        }
+    pub fn resolve_collisions(&mut self, walls: &[Rect]) -> Vec<Contact> {
+        walls
+            .iter()
+            .filter_map(|rect| self.resolve_collision(rect))
+            .collect()
     }
 
     pub fn width(&self) -> f32 {
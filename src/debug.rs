@@ -1,13 +1,42 @@
+use std::{collections::VecDeque, time::Duration};
+
 use ggez::{
-    graphics::{self, DrawParam, Rect},
+    graphics::{self, Color, DrawMode, DrawParam, MeshBuilder, Rect, Text},
     mint::Point2,
     Context, GameResult,
 };
 
-use crate::{collision::TilemapCollider, rendering::WorldDrawable, tilemap::Tilemap, world::World};
+use crate::{
+    collision::{Contact, DynamicCollider, TilemapCollider},
+    physics::PhysicsObject,
+    rendering::{WorldDrawParams, WorldDrawable, BLUE, GREEN, RED},
+    tilemap::Tilemap,
+    world::World,
+};
+
+const DEFAULT_FRAME_SAMPLES: usize = 120;
+const TARGET_FRAME_TIME: f32 = 1.0 / 60.0;
+pub(crate) const LINE_WIDTH: f32 = 1.0;
+const POINT_SIZE: f32 = 4.0;
+const NORMAL_LENGTH: f32 = 0.5;
+const CAMERA_TARGET_SIZE: f32 = 0.25;
 
 impl WorldDrawable for TilemapCollider {
-    fn draw_in_world(&self, ctx: &mut Context, world: &World, _: Rect) -> GameResult {
+    fn draw_in_world(&self, ctx: &mut Context, world: &World, rect: Rect) -> GameResult {
+        self.draw_in_world_with_params(ctx, world, rect, WorldDrawParams::default())
+    }
+
+    fn draw_in_world_with_params(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        _: Rect,
+        params: WorldDrawParams,
+    ) -> GameResult {
+        debug_assert!(
+            params.is_untransformed(),
+            "collider debug drawing only supports tint, alpha and z"
+        );
         let tiles = self.tiles_ref();
         for row in 0..tiles.len() {
             for col in 0..tiles[row].len() {
@@ -18,7 +47,7 @@ impl WorldDrawable for TilemapCollider {
                         x: col as f32,
                         y: row as f32,
                     });
-                    draw_rect_in_world(ctx, Rect::new(x, y, w, h), world)?
+                    draw_colored_rect_in_world(ctx, Rect::new(x, y, w, h), world, params.color())?
                 }
             }
         }
@@ -27,16 +56,397 @@ impl WorldDrawable for TilemapCollider {
 }
 
 pub fn draw_rect_in_world(ctx: &mut Context, rect: Rect, world: &World) -> GameResult {
+    draw_colored_rect_in_world(ctx, rect, world, graphics::WHITE)
+}
+
+pub fn draw_colored_rect_in_world(
+    ctx: &mut Context,
+    rect: Rect,
+    world: &World,
+    color: Color,
+) -> GameResult {
+    let mut builder = MeshBuilder::new();
+    add_rect(&mut builder, world, rect, color);
+    let mesh = builder.build(ctx)?;
+    graphics::draw(ctx, &mesh, DrawParam::default())
+}
+
+pub fn draw_line_in_world(
+    ctx: &mut Context,
+    from: Point2<f32>,
+    to: Point2<f32>,
+    world: &World,
+    color: Color,
+) -> GameResult {
+    let mut builder = MeshBuilder::new();
+    if !add_line(&mut builder, world, from, to, color)? {
+        return Ok(());
+    }
+    let mesh = builder.build(ctx)?;
+    graphics::draw(ctx, &mesh, DrawParam::default())
+}
+
+pub(crate) fn add_rect(builder: &mut MeshBuilder, world: &World, rect: Rect, color: Color) {
     let mut rect = rect;
     rect.translate(Point2 {
         x: -rect.w / 2.0,
         y: rect.h / 2.0,
     });
-    let mesh = graphics::Mesh::new_rectangle(
-        ctx,
-        graphics::DrawMode::stroke(1.0),
+    builder.rectangle(
+        DrawMode::stroke(LINE_WIDTH),
         world.world_to_screen_rect(rect),
-        graphics::WHITE,
-    )?;
-    graphics::draw(ctx, &mesh, DrawParam::default())
+        color,
+    );
+}
+
+pub(crate) fn add_line(
+    builder: &mut MeshBuilder,
+    world: &World,
+    from: Point2<f32>,
+    to: Point2<f32>,
+    color: Color,
+) -> GameResult<bool> {
+    let (from, to) = (
+        world.world_to_screen_pos(from),
+        world.world_to_screen_pos(to),
+    );
+    if (to.x - from.x).hypot(to.y - from.y) < f32::EPSILON {
+        return Ok(false);
+    }
+    builder.line(&[from, to], LINE_WIDTH, color)?;
+    Ok(true)
+}
+
+pub(crate) fn add_point(
+    builder: &mut MeshBuilder,
+    world: &World,
+    point: Point2<f32>,
+    color: Color,
+) {
+    let point = world.world_to_screen_pos(point);
+    builder.rectangle(
+        DrawMode::fill(),
+        Rect::new(
+            point.x - POINT_SIZE / 2.0,
+            point.y - POINT_SIZE / 2.0,
+            POINT_SIZE,
+            POINT_SIZE,
+        ),
+        color,
+    );
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DebugColors {
+    pub colliders: Color,
+    pub velocity: Color,
+    pub ground_checks: Color,
+    pub contacts: Color,
+    pub camera_target: Color,
+    pub dead_zone: Color,
+    pub chunks: Color,
+    pub frame_graph: Color,
+    pub frame_budget: Color,
+}
+
+impl Default for DebugColors {
+    fn default() -> Self {
+        Self {
+            colliders: GREEN,
+            velocity: BLUE,
+            ground_checks: RED,
+            contacts: Color::new(1.0, 1.0, 0.0, 1.0),
+            camera_target: graphics::WHITE,
+            dead_zone: Color::new(1.0, 1.0, 1.0, 0.5),
+            chunks: Color::new(1.0, 0.0, 1.0, 1.0),
+            frame_graph: GREEN,
+            frame_budget: RED,
+        }
+    }
+}
+
+pub struct FrameTimeGraph {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl FrameTimeGraph {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn push(&mut self, frame_time: Duration) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(frame_time.as_secs_f32());
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn samples(&self) -> &VecDeque<f32> {
+        &self.samples
+    }
+
+    pub fn latest(&self) -> Option<f32> {
+        self.samples.back().copied()
+    }
+
+    pub fn average(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f32>() / self.samples.len() as f32
+    }
+
+    pub fn max(&self) -> f32 {
+        self.samples.iter().copied().fold(0.0, f32::max)
+    }
+
+    pub fn fps(&self) -> f32 {
+        let average = self.average();
+        if average > 0.0 {
+            1.0 / average
+        } else {
+            0.0
+        }
+    }
+
+    pub fn points(&self, rect: Rect, max_frame_time: f32) -> Vec<Point2<f32>> {
+        let step = if self.capacity > 1 {
+            rect.w / (self.capacity - 1) as f32
+        } else {
+            0.0
+        };
+        self.samples
+            .iter()
+            .enumerate()
+            .map(|(index, sample)| Point2 {
+                x: rect.x + index as f32 * step,
+                y: rect.y + rect.h - frame_time_height(*sample, max_frame_time, rect.h),
+            })
+            .collect()
+    }
+}
+
+fn frame_time_height(frame_time: f32, max_frame_time: f32, height: f32) -> f32 {
+    if max_frame_time <= 0.0 {
+        return 0.0;
+    }
+    (frame_time / max_frame_time).clamp(0.0, 1.0) * height
+}
+
+pub struct DebugOverlay {
+    enabled: bool,
+    colors: DebugColors,
+    frame_times: FrameTimeGraph,
+    velocity_scale: f32,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            colors: DebugColors::default(),
+            frame_times: FrameTimeGraph::new(DEFAULT_FRAME_SAMPLES),
+            velocity_scale: 0.1,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn colors(&self) -> &DebugColors {
+        &self.colors
+    }
+
+    pub fn colors_mut(&mut self) -> &mut DebugColors {
+        &mut self.colors
+    }
+
+    pub fn set_velocity_scale(&mut self, velocity_scale: f32) {
+        self.velocity_scale = velocity_scale;
+    }
+
+    pub fn record_frame(&mut self, frame_time: Duration) {
+        self.frame_times.push(frame_time);
+    }
+
+    pub fn frame_times(&self) -> &FrameTimeGraph {
+        &self.frame_times
+    }
+
+    pub fn draw_collider(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        collider: &DynamicCollider,
+    ) -> GameResult {
+        draw_colored_rect_in_world(ctx, collider.rect(), world, self.colors.colliders)?;
+        let position = collider.position();
+        let velocity = collider.velocity();
+        draw_line_in_world(
+            ctx,
+            position,
+            Point2 {
+                x: position.x + velocity.x * self.velocity_scale,
+                y: position.y + velocity.y * self.velocity_scale,
+            },
+            world,
+            self.colors.velocity,
+        )
+    }
+
+    pub fn draw_points(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        points: &[Point2<f32>],
+        color: Color,
+    ) -> GameResult {
+        if points.is_empty() {
+            return Ok(());
+        }
+        let mut builder = MeshBuilder::new();
+        for point in points.iter() {
+            add_point(&mut builder, world, *point, color);
+        }
+        let mesh = builder.build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::default())
+    }
+
+    pub fn draw_ground_checks(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        points: &[Point2<f32>],
+    ) -> GameResult {
+        self.draw_points(ctx, world, points, self.colors.ground_checks)
+    }
+
+    pub fn draw_contacts(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        contacts: &[Contact],
+    ) -> GameResult {
+        let points: Vec<Point2<f32>> = contacts.iter().map(|contact| contact.point).collect();
+        self.draw_points(ctx, world, &points, self.colors.contacts)?;
+        for contact in contacts.iter() {
+            draw_line_in_world(
+                ctx,
+                contact.point,
+                Point2 {
+                    x: contact.point.x + contact.normal.x * NORMAL_LENGTH,
+                    y: contact.point.y + contact.normal.y * NORMAL_LENGTH,
+                },
+                world,
+                self.colors.contacts,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn draw_camera(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        target: Point2<f32>,
+        dead_zone: Rect,
+    ) -> GameResult {
+        if dead_zone.w > 0.0 || dead_zone.h > 0.0 {
+            draw_colored_rect_in_world(ctx, dead_zone, world, self.colors.dead_zone)?;
+        }
+        let cross = |x: f32, y: f32| Point2 {
+            x: target.x + x * CAMERA_TARGET_SIZE,
+            y: target.y + y * CAMERA_TARGET_SIZE,
+        };
+        draw_line_in_world(
+            ctx,
+            cross(-1.0, 0.0),
+            cross(1.0, 0.0),
+            world,
+            self.colors.camera_target,
+        )?;
+        draw_line_in_world(
+            ctx,
+            cross(0.0, -1.0),
+            cross(0.0, 1.0),
+            world,
+            self.colors.camera_target,
+        )
+    }
+
+    pub fn draw_chunks(&self, ctx: &mut Context, world: &World, chunks: &[Rect]) -> GameResult {
+        for chunk in chunks.iter() {
+            draw_colored_rect_in_world(ctx, *chunk, world, self.colors.chunks)?;
+        }
+        Ok(())
+    }
+
+    pub fn draw_frame_graph(&self, ctx: &mut Context, rect: Rect) -> GameResult {
+        let max_frame_time = self.frame_times.max().max(2.0 * TARGET_FRAME_TIME);
+        let budget_y =
+            rect.y + rect.h - frame_time_height(TARGET_FRAME_TIME, max_frame_time, rect.h);
+        let mut builder = MeshBuilder::new();
+        builder
+            .rectangle(DrawMode::fill(), rect, Color::new(0.0, 0.0, 0.0, 0.5))
+            .line(
+                &[
+                    Point2 {
+                        x: rect.x,
+                        y: budget_y,
+                    },
+                    Point2 {
+                        x: rect.x + rect.w,
+                        y: budget_y,
+                    },
+                ],
+                LINE_WIDTH,
+                self.colors.frame_budget,
+            )?;
+        let points = self.frame_times.points(rect, max_frame_time);
+        if points.len() > 1 {
+            builder.line(&points, LINE_WIDTH, self.colors.frame_graph)?;
+        }
+        let mesh = builder.build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::default())?;
+
+        let label = Text::new(format!(
+            "{:.0} FPS  {:.1} ms",
+            self.frame_times.fps(),
+            self.frame_times.latest().unwrap_or(0.0) * 1000.0
+        ));
+        let label_height = label.height(ctx) as f32;
+        graphics::draw(
+            ctx,
+            &label,
+            DrawParam::default()
+                .dest(Point2 {
+                    x: rect.x,
+                    y: rect.y - label_height,
+                })
+                .color(self.colors.frame_graph),
+        )
+    }
 }
@@ -0,0 +1,243 @@
+use std::{cell::RefCell, time::Duration};
+
+use ggez::{
+    graphics::{self, Color, DrawMode, DrawParam, MeshBuilder, Rect, Text},
+    mint::Point2,
+    Context, GameResult,
+};
+
+use crate::{
+    debug::{add_line, add_point, add_rect, LINE_WIDTH},
+    world::World,
+};
+
+const CIRCLE_TOLERANCE: f32 = 0.5;
+
+thread_local! {
+    static DEBUG_DRAW: RefCell<DebugDraw> = RefCell::new(DebugDraw::new());
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DebugShape {
+    Line { from: Point2<f32>, to: Point2<f32> },
+    Rect(Rect),
+    Circle { center: Point2<f32>, radius: f32 },
+    Point(Point2<f32>),
+    Text { position: Point2<f32>, text: String },
+}
+
+impl DebugShape {
+    pub fn line(from: Point2<f32>, to: Point2<f32>) -> Self {
+        DebugShape::Line { from, to }
+    }
+
+    pub fn circle(center: Point2<f32>, radius: f32) -> Self {
+        DebugShape::Circle { center, radius }
+    }
+
+    pub fn text(position: Point2<f32>, text: &str) -> Self {
+        DebugShape::Text {
+            position,
+            text: text.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DebugCommand {
+    pub shape: DebugShape,
+    pub color: Color,
+    pub remaining: f32,
+    drawn: bool,
+}
+
+impl DebugCommand {
+    pub fn is_expired(&self) -> bool {
+        self.drawn && self.remaining <= 0.0
+    }
+}
+
+pub struct DebugDraw {
+    commands: Vec<DebugCommand>,
+    enabled: bool,
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugDraw {
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            enabled: false,
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.commands.clear();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn push(&mut self, shape: DebugShape, color: Color, lifetime: Option<Duration>) {
+        if !self.enabled {
+            return;
+        }
+        self.commands.push(DebugCommand {
+            shape,
+            color,
+            remaining: lifetime.map_or(0.0, |lifetime| lifetime.as_secs_f32()),
+            drawn: false,
+        });
+    }
+
+    pub fn line(&mut self, from: Point2<f32>, to: Point2<f32>, color: Color) {
+        self.push(DebugShape::line(from, to), color, None);
+    }
+
+    pub fn rect(&mut self, rect: Rect, color: Color) {
+        self.push(DebugShape::Rect(rect), color, None);
+    }
+
+    pub fn circle(&mut self, center: Point2<f32>, radius: f32, color: Color) {
+        self.push(DebugShape::circle(center, radius), color, None);
+    }
+
+    pub fn point(&mut self, point: Point2<f32>, color: Color) {
+        self.push(DebugShape::Point(point), color, None);
+    }
+
+    pub fn text(&mut self, position: Point2<f32>, text: &str, color: Color) {
+        self.push(DebugShape::text(position, text), color, None);
+    }
+
+    pub fn update(&mut self, deltatime: Duration) {
+        let dt = deltatime.as_secs_f32();
+        for command in self.commands.iter_mut() {
+            if command.drawn {
+                command.remaining -= dt;
+            }
+        }
+        self.commands.retain(|command| !command.is_expired());
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    pub fn commands(&self) -> &[DebugCommand] {
+        &self.commands
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn mark_drawn(&mut self) {
+        for command in self.commands.iter_mut() {
+            command.drawn = true;
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, world: &World) -> GameResult {
+        if self.commands.is_empty() {
+            return Ok(());
+        }
+        let mut builder = MeshBuilder::new();
+        let mut has_geometry = false;
+        let mut labels = Vec::new();
+        for command in self.commands.iter() {
+            let color = command.color;
+            match &command.shape {
+                DebugShape::Line { from, to } => {
+                    if add_line(&mut builder, world, *from, *to, color)? {
+                        has_geometry = true;
+                    }
+                }
+                DebugShape::Rect(rect) => {
+                    add_rect(&mut builder, world, *rect, color);
+                    has_geometry = true;
+                }
+                DebugShape::Circle { center, radius } => {
+                    let screen_radius = world
+                        .world_to_screen_rect(Rect::new(0.0, 0.0, *radius, *radius))
+                        .w
+                        .abs();
+                    if screen_radius > 0.0 {
+                        builder.circle(
+                            DrawMode::stroke(LINE_WIDTH),
+                            world.world_to_screen_pos(*center),
+                            screen_radius,
+                            CIRCLE_TOLERANCE,
+                            color,
+                        );
+                        has_geometry = true;
+                    }
+                }
+                DebugShape::Point(point) => {
+                    add_point(&mut builder, world, *point, color);
+                    has_geometry = true;
+                }
+                DebugShape::Text { position, text } => {
+                    labels.push((world.world_to_screen_pos(*position), text.as_str(), color));
+                }
+            }
+        }
+        if has_geometry {
+            let mesh = builder.build(ctx)?;
+            graphics::draw(ctx, &mesh, DrawParam::default())?;
+        }
+        for (position, text, color) in labels {
+            graphics::draw(
+                ctx,
+                &Text::new(text),
+                DrawParam::default().dest(position).color(color),
+            )?;
+        }
+        self.mark_drawn();
+        Ok(())
+    }
+}
+
+pub fn with_debug_draw<F, R>(f: F) -> R
+where
+    F: FnOnce(&mut DebugDraw) -> R,
+{
+    DEBUG_DRAW.with(|debug_draw| f(&mut debug_draw.borrow_mut()))
+}
+
+pub fn queue(shape: DebugShape, color: Color, lifetime: Option<Duration>) {
+    with_debug_draw(|debug_draw| debug_draw.push(shape, color, lifetime));
+}
+
+pub fn line(from: Point2<f32>, to: Point2<f32>, color: Color) {
+    with_debug_draw(|debug_draw| debug_draw.line(from, to, color));
+}
+
+pub fn rect(rect: Rect, color: Color) {
+    with_debug_draw(|debug_draw| debug_draw.rect(rect, color));
+}
+
+pub fn circle(center: Point2<f32>, radius: f32, color: Color) {
+    with_debug_draw(|debug_draw| debug_draw.circle(center, radius, color));
+}
+
+pub fn point(point: Point2<f32>, color: Color) {
+    with_debug_draw(|debug_draw| debug_draw.point(point, color));
+}
+
+pub fn text(position: Point2<f32>, text: &str, color: Color) {
+    with_debug_draw(|debug_draw| debug_draw.text(position, text, color));
+}
@@ -1,22 +1,43 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    f32::consts::PI,
+    rc::Rc,
+    time::Duration,
+};
 
 use ggez::{
-    audio::{SoundSource, Source},
-    event::{EventHandler, KeyCode, KeyMods},
+    audio::{SoundData, SoundSource, Source},
+    conf::FullscreenType,
+    event::{Button, EventHandler, GamepadId, KeyCode, KeyMods},
     graphics::{self, Color, FilterMode, Image, Rect},
     input::keyboard,
     mint::{Point2, Vector2},
-    timer, Context, GameResult,
+    timer, Context, GameError, GameResult,
 };
 use rand::Rng;
 
 use crate::{
-    animation::{SpriteAnimator, SpriteSheetAnimation},
-    camera::{Camera, FollowDirection, SmoothCamera},
-    collision::{DynamicCollider, TilemapCollider},
-    movement::MovementController,
+    animation::{
+        BlendSettings, Condition, HierarchicalStateMachine, SpriteAnimator, SpriteSheetAnimation,
+        StateMachine,
+    },
+    assets::{AssetEvent, AssetHandle, AssetManager, AssetManifest, Handle, Level, LoadProgress},
+    camera::{Camera, CameraEffects, FollowDirection, ScreenShake, SmoothCamera},
+    collision::{Contact, DynamicCollider, TilemapCollider},
+    debug::DebugOverlay,
+    debug_draw::{self, DebugShape},
+    lighting::{LightMap, PointLight},
+    movement::{MovementConfig, MovementController, MovementParams},
+    particles::{EmitterSettings, ParticleEmitter},
     physics::PhysicsObject,
-    rendering::{SpriteSheet, TilemapRenderer, WorldDrawable},
+    pixel_perfect::PixelPerfect,
+    post_process::{PostEffect, PostEffectKind, PostProcessChain},
+    render_queue::{RenderQueue, SortKey, SpriteBatches},
+    rendering::{SpriteSheet, TilemapRenderer, WorldDrawParams},
+    scaling::{ScalingPolicy, ScreenScaler},
+    ui::{
+        Anchor, Label, Menu, MenuEvent, ProgressBar, Tweak, TweakPanel, UiElement, UiInput, UiLayer,
+    },
     world::World,
 };
 
@@ -25,6 +46,23 @@ const LEFT_KEY: KeyCode = KeyCode::Left;
 const RIGHT_KEY: KeyCode = KeyCode::Right;
 const JUMP_KEY: KeyCode = KeyCode::Z;
 const QUIT_KEY: KeyCode = KeyCode::Escape;
+const PIXEL_PERFECT_KEY: KeyCode = KeyCode::P;
+const LIGHTING_KEY: KeyCode = KeyCode::L;
+const CRT_KEY: KeyCode = KeyCode::C;
+const VIGNETTE_KEY: KeyCode = KeyCode::V;
+const COLOR_GRADE_KEY: KeyCode = KeyCode::G;
+const PIXELATE_KEY: KeyCode = KeyCode::X;
+const FULLSCREEN_KEY: KeyCode = KeyCode::F11;
+const PAUSE_KEY: KeyCode = KeyCode::Return;
+const DEBUG_KEY: KeyCode = KeyCode::F3;
+const TWEAK_KEY: KeyCode = KeyCode::F4;
+const SAVE_PRESET_KEY: KeyCode = KeyCode::F5;
+const TWEAK_PREVIOUS_KEY: KeyCode = KeyCode::PageUp;
+const TWEAK_NEXT_KEY: KeyCode = KeyCode::PageDown;
+const TWEAK_DECREASE_KEY: KeyCode = KeyCode::Minus;
+const TWEAK_INCREASE_KEY: KeyCode = KeyCode::Equals;
+const JUMP_BUTTON: Button = Button::South;
+const PAUSE_BUTTON: Button = Button::Start;
 
 // Asset paths
 const PLAYER_IDLE: &'static str = "/placeholder.png";
@@ -33,6 +71,7 @@ const PLAYER_FALL: &'static str = "/fall.png";
 const PLAYER_WALK: &'static str = "/walking.png";
 const GROUND_TILES: &'static str = "/ground.png";
 const JUMP_SOUND: &'static str = "/jump.wav";
+const CAVE_LEVEL: &str = "/cave.txt";
 
 // Player params
 const SPAWN_POSITION: Point2<f32> = Point2 { x: 0.0, y: 0.5 };
@@ -51,12 +90,26 @@ const GROUND_CHECK_OFFSETS: &[Vector2<f32>] = &[
     Vector2 { x: 0.98, y: -1.2 },
 ];
 const MIDAIR_JUMPS: u32 = 1;
+const SPEED_PARAM: &str = "speed";
+const VERTICAL_VELOCITY_PARAM: &str = "vertical_velocity";
+const GROUNDED_PARAM: &str = "grounded";
+const JUMP_FALL_FADE: f32 = 0.15;
+const MOVEMENT_CONFIG: &str = "movement.json";
+const MOVEMENT_TWEAK_STEPS: [(&str, f32); 6] = [
+    ("move_force", 5.0),
+    ("jump_impulse", 0.5),
+    ("max_speed", 0.5),
+    ("move_speed_decay", 5.0),
+    ("jump_decay", 2.0),
+    ("gravity_acceleration", 2.0),
+];
 
 // Cave params
 const TEMPLATE_WIDTH: u32 = 31;
 const TEMPLATE_HEIGHT: u32 = 15;
 const FLOOR_CEIL_LIMITS: (u32, u32) = (7, 8);
 const TEMPLATE_CONNECTIONS: (u32, u32) = (5, 10);
+const SPAWN_FRAGMENT: i32 = 0;
 const STEP: u32 = 2;
 const TILE_WIDTH: f32 = 1.0;
 const TILE_HEIGHT: f32 = 1.0;
@@ -83,23 +136,74 @@ const U: u32 = 16;
 pub const SCREEN_WIDTH: f32 = 1200.0;
 pub const SCREEN_HEIGHT: f32 = 600.0;
 const DISTANCE: f32 = 7.0;
+const VIRTUAL_WIDTH: u16 = 600;
+const VIRTUAL_HEIGHT: u16 = 300;
+const PIXELS_PER_UNIT: f32 = 32.0;
 
 // Other params
 const BG_COLOR: Color = Color::new(0.1, 0.08, 0.05, 1.0);
+const AMBIENT_LIGHT: f32 = 0.35;
+const TORCH_LIGHT: usize = 0;
+const TORCH_RADIUS: f32 = 9.0;
+const TORCH_COLOR: Color = Color::new(1.0, 0.85, 0.6, 1.0);
+const CAVE_LAYER: i32 = 0;
+const PLAYER_LAYER: i32 = 1;
+const PARTICLE_LAYER: i32 = 2;
+const LETTERBOX_COLOR: Color = Color::new(0.0, 0.0, 0.0, 1.0);
 const CAMERA_SMOOTHNESS: f32 = 1.5;
 const CAMERA_AHEAD_DISTANCE: f32 = 4.0;
+const CAMERA_SHAKE_OFFSET: Vector2<f32> = Vector2 { x: 0.4, y: 0.4 };
+const CAMERA_SHAKE_FREQUENCY: f32 = 25.0;
+const CAMERA_SHAKE_DECAY: f32 = 1.5;
+const LANDING_SHAKE_SPEED: f32 = 25.0;
+const LANDING_TRAUMA: f32 = 0.5;
+const LANDING_DUST_SPEED: f32 = 10.0;
+const LANDING_DUST_PARTICLES: usize = 12;
+const DUST_COLOR: Color = Color::new(0.6, 0.5, 0.4, 0.8);
+const LANDING_FLASH_COLOR: Color = Color::new(1.0, 0.9, 0.8, 0.35);
+const LANDING_FLASH_DURATION: f32 = 0.2;
+const PIXELATE_SIZE: f32 = 4.0;
+const HUD_MARGIN: f32 = 16.0;
+const HUD_TEXT_SIZE: f32 = 24.0;
+const HUD_COLOR: Color = Color::new(1.0, 0.95, 0.85, 1.0);
+const JUMP_BAR_SIZE: (f32, f32) = (96.0, 12.0);
+const JUMP_BAR_COLOR: Color = Color::new(0.4, 0.8, 1.0, 1.0);
+const LOADING_BAR_SIZE: (f32, f32) = (320.0, 16.0);
+const PAUSE_OVERLAY_COLOR: Color = Color::new(0.0, 0.0, 0.0, 0.6);
+const MENU_RESUME: usize = 0;
+const MENU_LIGHTING: usize = 1;
+const MENU_PIXEL_PERFECT: usize = 2;
+const MENU_QUIT: usize = 3;
+const FRAME_GRAPH_SIZE: (f32, f32) = (240.0, 60.0);
+const COLLISION_QUERY_COLOR: Color = Color::new(1.0, 0.5, 0.0, 1.0);
+const JUMP_MARKER_COLOR: Color = Color::new(0.4, 0.8, 1.0, 1.0);
+const JUMP_MARKER_RADIUS: f32 = 0.3;
+const JUMP_MARKER_LIFETIME: f32 = 1.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum PlayerAnimation {
+    Grounded,
+    Airborne,
+    Idle,
+    Walk,
+    Jump,
+    Fall,
+}
 
 struct Player {
-    animator: SpriteAnimator<Vector2<f32>>,
+    animator: SpriteAnimator<PlayerAnimation>,
     controller: MovementController,
     orientation: i8,
     can_jump: bool,
     midair_jumps_left: u32,
-    jump_sound: Source,
+    jump_sound: Option<Source>,
+    jump_sound_handle: Option<Handle<SoundData>>,
+    images: Vec<(PlayerAnimation, Handle<Image>)>,
+    dust: ParticleEmitter,
 }
 
 impl Player {
-    fn new(ctx: &mut Context) -> Self {
+    fn new(ctx: &mut Context, assets: &mut AssetManager) -> GameResult<Self> {
         // Controller init
         let body = DynamicCollider::from_rect(
             Rect::new(
@@ -110,75 +214,168 @@ impl Player {
             ),
             MASS,
         );
-        let controller = MovementController::from_components(
-            body,
-            MOVE_FORCE,
-            JUMP_IMPULSE,
-            MAX_SPEED,
-            MOVE_SPEED_DECAY,
-            GRAVITY_ACCELERATION,
-            GROUND_CHECK_OFFSETS,
-        );
+        let controller =
+            MovementController::from_params(body, movement_params(), GROUND_CHECK_OFFSETS);
 
         // Animator init
-        let idle_image =
-            Image::new(ctx, PLAYER_IDLE).expect(&format!("Failed to load {}", PLAYER_IDLE));
-        let idle_sprites = SpriteSheet::new(idle_image, 1, 1, 1);
+        let idle_image = assets.load_image_or_fallback(ctx, PLAYER_IDLE)?;
+        let idle_sprites = assets.sprite_sheet(&idle_image, 1, 1, 1)?;
         let idle_animation = SpriteSheetAnimation::new(idle_sprites, 1.0);
 
-        let jump_image =
-            Image::new(ctx, PLAYER_JUMP).expect(&format!("Failed to load {}", PLAYER_JUMP));
-        let jump_sprites = SpriteSheet::new(jump_image, 1, 1, 1);
+        let jump_image = assets.load_image_or_fallback(ctx, PLAYER_JUMP)?;
+        let jump_sprites = assets.sprite_sheet(&jump_image, 1, 1, 1)?;
         let jump_animation = SpriteSheetAnimation::new(jump_sprites, 1.0);
 
-        let fall_image =
-            Image::new(ctx, PLAYER_FALL).expect(&format!("Failed to load {}", PLAYER_FALL));
-        let fall_sprites = SpriteSheet::new(fall_image, 1, 1, 1);
+        let fall_image = assets.load_image_or_fallback(ctx, PLAYER_FALL)?;
+        let fall_sprites = assets.sprite_sheet(&fall_image, 1, 1, 1)?;
         let fall_animation = SpriteSheetAnimation::new(fall_sprites, 1.0);
 
-        let walking_image =
-            Image::new(ctx, PLAYER_WALK).expect(&format!("Failed to load {}", PLAYER_WALK));
-        let walking_sprites = SpriteSheet::new(walking_image, 3, 2, 6);
+        let walking_image = assets.load_image_or_fallback(ctx, PLAYER_WALK)?;
+        let walking_sprites = assets.sprite_sheet(&walking_image, 3, 2, 6)?;
         let walking_animation = SpriteSheetAnimation::new(walking_sprites, 30.0);
 
-        let mut animator: SpriteAnimator<Vector2<f32>> = SpriteAnimator::from_animations(vec![
-            idle_animation,
-            walking_animation,
-            jump_animation,
-            fall_animation,
-        ]);
-        animator.add_rule(0, 1, |velocity| velocity.x.abs() > MAX_SPEED / 10.0);
-        animator.add_rule(1, 0, |velocity| velocity.x.abs() < MAX_SPEED / 10.0);
-        animator.add_rule(0, 2, |velocity| velocity.y > GRAVITY_ACCELERATION / 35.0);
-        animator.add_rule(1, 2, |velocity| velocity.y > GRAVITY_ACCELERATION / 35.0);
-        animator.add_rule(0, 3, |velocity| velocity.y < -GRAVITY_ACCELERATION / 35.0);
-        animator.add_rule(1, 3, |velocity| velocity.y < -GRAVITY_ACCELERATION / 35.0);
-        animator.add_rule(2, 3, |velocity| velocity.y <= 0.0);
-        animator.add_rule(3, 0, |velocity| velocity.y >= 0.0);
-
-        let jump_sound =
-            Source::new(ctx, JUMP_SOUND).expect(&format!("Failed to load {}", JUMP_SOUND));
+        let walk_threshold = MAX_SPEED / 10.0;
+        let airborne_threshold = GRAVITY_ACCELERATION / 35.0;
 
-        Self {
+        let mut grounded = StateMachine::with_initial_state(PlayerAnimation::Idle);
+        grounded.add_transition(
+            PlayerAnimation::Idle,
+            PlayerAnimation::Walk,
+            vec![Condition::greater(SPEED_PARAM, walk_threshold)],
+        );
+        grounded.add_transition(
+            PlayerAnimation::Walk,
+            PlayerAnimation::Idle,
+            vec![Condition::less(SPEED_PARAM, walk_threshold)],
+        );
+
+        let mut airborne = StateMachine::with_initial_state(PlayerAnimation::Fall);
+        airborne.add_transition(
+            PlayerAnimation::Fall,
+            PlayerAnimation::Jump,
+            vec![Condition::greater(
+                VERTICAL_VELOCITY_PARAM,
+                airborne_threshold,
+            )],
+        );
+        airborne.add_transition(
+            PlayerAnimation::Jump,
+            PlayerAnimation::Fall,
+            vec![Condition::less(VERTICAL_VELOCITY_PARAM, f32::EPSILON)],
+        );
+
+        let mut locomotion =
+            HierarchicalStateMachine::with_initial_state(PlayerAnimation::Grounded);
+        locomotion.add_sub_machine(
+            PlayerAnimation::Grounded,
+            HierarchicalStateMachine::new(grounded),
+        );
+        locomotion.add_sub_machine(
+            PlayerAnimation::Airborne,
+            HierarchicalStateMachine::new(airborne),
+        );
+
+        let mut animator = SpriteAnimator::with_state_machine(
+            vec![
+                (PlayerAnimation::Idle, idle_animation),
+                (PlayerAnimation::Walk, walking_animation),
+                (PlayerAnimation::Jump, jump_animation),
+                (PlayerAnimation::Fall, fall_animation),
+            ],
+            locomotion,
+        );
+        animator.add_transition(
+            PlayerAnimation::Grounded,
+            PlayerAnimation::Airborne,
+            vec![Condition::greater(
+                VERTICAL_VELOCITY_PARAM,
+                airborne_threshold,
+            )],
+        );
+        animator.add_transition(
+            PlayerAnimation::Grounded,
+            PlayerAnimation::Airborne,
+            vec![Condition::less(
+                VERTICAL_VELOCITY_PARAM,
+                -airborne_threshold,
+            )],
+        );
+        animator.add_transition(
+            PlayerAnimation::Airborne,
+            PlayerAnimation::Grounded,
+            vec![
+                Condition::is_true(GROUNDED_PARAM),
+                Condition::less(VERTICAL_VELOCITY_PARAM, f32::EPSILON),
+            ],
+        );
+
+        animator.set_blend(
+            PlayerAnimation::Jump,
+            PlayerAnimation::Fall,
+            BlendSettings::cross_fade(Duration::from_secs_f32(JUMP_FALL_FADE)),
+        );
+
+        let jump_sound_handle = assets.try_load::<SoundData>(ctx, JUMP_SOUND);
+        let jump_sound = match &jump_sound_handle {
+            Some(handle) => Some(assets.source(ctx, handle)?),
+            None => None,
+        };
+
+        Ok(Self {
             controller,
             animator,
             orientation: 1,
             can_jump: false,
             midair_jumps_left: MIDAIR_JUMPS,
             jump_sound,
+            jump_sound_handle,
+            images: vec![
+                (PlayerAnimation::Idle, idle_image),
+                (PlayerAnimation::Walk, walking_image),
+                (PlayerAnimation::Jump, jump_image),
+                (PlayerAnimation::Fall, fall_image),
+            ],
+            dust: ParticleEmitter::new(dust_settings(), SPAWN_POSITION),
+        })
+    }
+
+    fn reload_asset(
+        &mut self,
+        ctx: &mut Context,
+        assets: &AssetManager,
+        handle: AssetHandle,
+    ) -> GameResult {
+        if let Some(sound) = self
+            .jump_sound_handle
+            .as_ref()
+            .filter(|sound| **sound == handle)
+        {
+            self.jump_sound = Some(assets.source(ctx, sound)?);
+        }
+        for (state, image) in self.images.iter() {
+            if *image != handle {
+                continue;
+            }
+            if let (Some(animation), Some(image)) =
+                (self.animator.animation_mut(*state), assets.image(image))
+            {
+                animation.set_image(image);
+            }
         }
+        Ok(())
     }
 
     fn draw(&self, ctx: &mut Context, world: &World) -> GameResult {
-        self.animator.get_drawable().draw_in_world(
+        self.animator.draw_in_world_with_params(
             ctx,
             world,
             Rect::new(
                 self.controller.collider().position().x,
                 self.controller.collider().position().y,
-                self.orientation as f32 * PLAYER_WIDTH,
+                PLAYER_WIDTH,
                 PLAYER_HEIGHT,
             ),
+            WorldDrawParams::new().flip_x(self.orientation < 0),
         )
     }
 }
@@ -188,42 +385,87 @@ struct TilemapCave {
     tilemap_renderers: VecDeque<TilemapRenderer>,
     tilemap_colliders: VecDeque<TilemapCollider>,
     tile_hashmap: TileHashmap,
-    ground_image: Image,
+    ground_sprites: SpriteSheet,
+    ground_image: Handle<Image>,
+    spawn_level: Option<Handle<Level>>,
+    spawn_template: Option<Vec<Vec<u32>>>,
 }
 
 impl TilemapCave {
-    fn new(ctx: &mut Context) -> Self {
+    fn new(ctx: &mut Context, assets: &mut AssetManager) -> GameResult<Self> {
         let tile_hashmap = tile_hashmap();
 
-        let mut ground_image =
-            Image::new(ctx, GROUND_TILES).expect(&format!("Failed to load {}", GROUND_TILES));
-        ground_image.set_filter(FilterMode::Nearest);
-
-        let tilemap_renderers: VecDeque<_> = (-1..=1)
-            .into_iter()
-            .map(|fragment_index| {
-                TilemapCave::generate_tilemap_renderer(&ground_image, &tile_hashmap, fragment_index)
-            })
-            .collect();
-        let tilemap_colliders: VecDeque<_> = tilemap_renderers
-            .iter()
-            .map(|renderer| TilemapCollider::from(renderer))
-            .collect();
+        let ground_image = assets.load_image_or_fallback(ctx, GROUND_TILES)?;
+        assets.set_filter(&ground_image, FilterMode::Nearest);
+        let ground_sprites = assets.sprite_sheet(&ground_image, 4, 4, 16)?;
 
-        Self {
+        let spawn_level = assets.try_load::<Level>(ctx, CAVE_LEVEL);
+        let spawn_template = spawn_level
+            .as_ref()
+            .and_then(|level| assets.level(level))
+            .and_then(|level| match level_template(level, &tile_hashmap) {
+                Ok(template) => Some(template),
+                Err(err) => {
+                    println!("{}", err);
+                    None
+                }
+            });
+
+        let mut cave = Self {
             current_fragment: 0,
             tile_hashmap,
-            tilemap_renderers,
-            tilemap_colliders,
+            tilemap_renderers: VecDeque::new(),
+            tilemap_colliders: VecDeque::new(),
+            ground_sprites,
             ground_image,
+            spawn_level,
+            spawn_template,
+        };
+        for fragment_index in -1..=1 {
+            let renderer = cave.generate_tilemap_renderer(fragment_index);
+            cave.tilemap_colliders
+                .push_back(TilemapCollider::from(&renderer));
+            cave.tilemap_renderers.push_back(renderer);
         }
+        Ok(cave)
     }
 
-    fn draw(&self, ctx: &mut Context, world: &World) -> GameResult {
+    fn reload_asset(&mut self, assets: &AssetManager, handle: AssetHandle) {
+        if self.ground_image == handle {
+            if let Some(image) = assets.image(&self.ground_image) {
+                for renderer in self.tilemap_renderers.iter() {
+                    renderer.set_image(Rc::clone(&image));
+                }
+                self.ground_sprites.set_image(image);
+            }
+        }
+        if let Some(level) = self.spawn_level.as_ref().filter(|level| **level == handle) {
+            match assets
+                .level(level)
+                .map(|level| level_template(level, &self.tile_hashmap))
+            {
+                Some(Ok(template)) => self.set_spawn_template(template),
+                Some(Err(err)) => println!("{}", err),
+                None => (),
+            }
+        }
+    }
+
+    fn set_spawn_template(&mut self, template: Vec<Vec<u32>>) {
+        let index = SPAWN_FRAGMENT - (self.current_fragment - 1);
+        if (0..self.tilemap_renderers.len() as i32).contains(&index) {
+            let index = index as usize;
+            let renderer = &mut self.tilemap_renderers[index];
+            renderer.set_tiles(&template.iter().map(|row| &row[..]).collect::<Vec<_>>());
+            self.tilemap_colliders[index] = TilemapCollider::from(&*renderer);
+        }
+        self.spawn_template = Some(template);
+    }
+
+    fn submit(&self, queue: &mut RenderQueue) {
         for renderer in self.tilemap_renderers.iter() {
-            renderer.draw_in_world(ctx, &world, Rect::default())?;
+            renderer.submit(queue, CAVE_LAYER, WorldDrawParams::default());
         }
-        Ok(())
     }
 
     fn get_collisions(&self, rect: Rect) -> Vec<Rect> {
@@ -255,11 +497,7 @@ impl TilemapCave {
         self.current_fragment += 1;
         self.tilemap_renderers.pop_front();
         self.tilemap_colliders.pop_front();
-        let new_renderer = TilemapCave::generate_tilemap_renderer(
-            &self.ground_image,
-            &self.tile_hashmap,
-            self.current_fragment + 1,
-        );
+        let new_renderer = self.generate_tilemap_renderer(self.current_fragment + 1);
         self.tilemap_colliders
             .push_back(TilemapCollider::from(&new_renderer));
         self.tilemap_renderers.push_back(new_renderer);
@@ -269,37 +507,31 @@ impl TilemapCave {
         self.current_fragment -= 1;
         self.tilemap_renderers.pop_back();
         self.tilemap_colliders.pop_back();
-        let new_renderer = TilemapCave::generate_tilemap_renderer(
-            &self.ground_image,
-            &self.tile_hashmap,
-            self.current_fragment - 1,
-        );
+        let new_renderer = self.generate_tilemap_renderer(self.current_fragment - 1);
         self.tilemap_colliders
             .push_front(TilemapCollider::from(&new_renderer));
         self.tilemap_renderers.push_front(new_renderer);
     }
 
-    fn generate_tilemap_renderer(
-        image: &Image,
-        tile_hashmap: &TileHashmap,
-        fragment_index: i32,
-    ) -> TilemapRenderer {
-        let ground_sprites = SpriteSheet::new(image.clone(), 4, 4, 16);
-        let ground_template = generate_ground_template(
-            TEMPLATE_WIDTH,
-            TEMPLATE_HEIGHT,
-            TEMPLATE_CONNECTIONS,
-            TEMPLATE_CONNECTIONS,
-            FLOOR_CEIL_LIMITS,
-            STEP,
-            &tile_hashmap,
-        );
+    fn generate_tilemap_renderer(&self, fragment_index: i32) -> TilemapRenderer {
+        let ground_template = match &self.spawn_template {
+            Some(template) if fragment_index == SPAWN_FRAGMENT => template.clone(),
+            _ => generate_ground_template(
+                TEMPLATE_WIDTH,
+                TEMPLATE_HEIGHT,
+                TEMPLATE_CONNECTIONS,
+                TEMPLATE_CONNECTIONS,
+                FLOOR_CEIL_LIMITS,
+                STEP,
+                &self.tile_hashmap,
+            ),
+        };
         let ground_template = &ground_template
             .iter()
             .map(|row| &(*row)[..])
             .collect::<Vec<_>>()[..];
         let tilemap_renderer = TilemapRenderer::from_components(
-            ground_sprites,
+            self.ground_sprites.clone(),
             ground_template,
             TILE_WIDTH,
             TILE_HEIGHT,
@@ -312,29 +544,260 @@ impl TilemapCave {
     }
 }
 
+struct Hud {
+    layer: UiLayer,
+    distance: Label,
+    jumps_label: Label,
+    jumps: ProgressBar,
+    pause_title: Label,
+    pause_menu: Menu,
+    tweaks: TweakPanel,
+    tweaks_visible: bool,
+}
+
+impl Hud {
+    fn new() -> Self {
+        let mut layer = UiLayer::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+        layer.set_margin(HUD_MARGIN);
+        let label = |text: &str| Label::new(text).text_size(HUD_TEXT_SIZE).color(HUD_COLOR);
+        Self {
+            layer,
+            distance: label("Distance: 0").placement(Anchor::TopLeft, Vector2 { x: 0.0, y: 0.0 }),
+            jumps_label: label("Jump").placement(
+                Anchor::TopRight,
+                Vector2 {
+                    x: -JUMP_BAR_SIZE.0 - HUD_MARGIN / 2.0,
+                    y: 0.0,
+                },
+            ),
+            jumps: ProgressBar::new(MIDAIR_JUMPS as f32, JUMP_BAR_SIZE.0, JUMP_BAR_SIZE.1)
+                .colors(JUMP_BAR_COLOR, Color::new(0.0, 0.0, 0.0, 0.5))
+                .border(HUD_COLOR, 1.0)
+                .placement(
+                    Anchor::TopRight,
+                    Vector2 {
+                        x: 0.0,
+                        y: (HUD_TEXT_SIZE - JUMP_BAR_SIZE.1) / 2.0,
+                    },
+                ),
+            pause_title: label("Paused").placement(
+                Anchor::Center,
+                Vector2 {
+                    x: 0.0,
+                    y: -3.0 * HUD_TEXT_SIZE,
+                },
+            ),
+            pause_menu: Menu::with_labels(&["Resume", "Lighting", "Pixel perfect", "Quit"])
+                .text_size(HUD_TEXT_SIZE),
+            tweaks: movement_tweaks(&movement_params()).text_size(HUD_TEXT_SIZE),
+            tweaks_visible: false,
+        }
+    }
+
+    fn set_movement_params(&mut self, params: &MovementParams) {
+        for field in MovementParams::FIELDS.iter() {
+            if let Some(value) = params.get(field) {
+                self.tweaks.set_value(field, value);
+            }
+        }
+    }
+
+    fn movement_params(&self, current: &MovementParams) -> MovementParams {
+        let mut params = *current;
+        for tweak in self.tweaks.tweaks().iter() {
+            params.set(&tweak.label, tweak.value);
+        }
+        params
+    }
+
+    fn update(&mut self, distance: f32, midair_jumps_left: u32) {
+        self.distance
+            .set_text(&format!("Distance: {}", distance.max(0.0) as u32));
+        self.jumps.set_value(midair_jumps_left as f32);
+    }
+
+    fn draw(&self, ctx: &mut Context, paused: bool) -> GameResult {
+        self.layer.draw(ctx, |ctx, screen| {
+            self.distance.draw_ui(ctx, screen)?;
+            self.jumps_label.draw_ui(ctx, screen)?;
+            self.jumps.draw_ui(ctx, screen)?;
+            if self.tweaks_visible {
+                self.tweaks.draw_ui(ctx, screen)?;
+            }
+            if paused {
+                let overlay = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    self.layer.screen_rect(),
+                    PAUSE_OVERLAY_COLOR,
+                )?;
+                graphics::draw(ctx, &overlay, graphics::DrawParam::default())?;
+                self.pause_title.draw_ui(ctx, screen)?;
+                self.pause_menu.draw_ui(ctx, screen)?;
+            }
+            Ok(())
+        })
+    }
+}
+
 pub struct EzPlatform {
     world: World,
     camera: SmoothCamera,
+    camera_effects: CameraEffects,
     cave: TilemapCave,
     player: Player,
+    pixel_perfect: PixelPerfect,
+    pixel_perfect_enabled: bool,
+    light_map: LightMap,
+    lighting_enabled: bool,
+    post_process: PostProcessChain,
+    hud: Hud,
+    paused: bool,
+    best_distance: f32,
+    debug: DebugOverlay,
+    contacts: Vec<Contact>,
+    sprite_batches: SpriteBatches,
+    movement_config: MovementConfig,
+    assets: AssetManager,
+    scaler: ScreenScaler,
+    window_size: (f32, f32),
+    fullscreen: bool,
 }
 
 impl EzPlatform {
-    pub fn new(ctx: &mut Context) -> EzPlatform {
-        let player = Player::new(ctx);
+    pub fn new(ctx: &mut Context) -> GameResult<EzPlatform> {
+        let mut assets = AssetManager::new(ctx);
+        let preloaded = assets.load_manifest(ctx, &asset_manifest(), draw_loading_screen)?;
+        let player = Player::new(ctx, &mut assets)?;
 
         let world: World = World::new(SCREEN_WIDTH, SCREEN_HEIGHT, DISTANCE);
 
         let mut camera = SmoothCamera::new(world.camera_position(), CAMERA_SMOOTHNESS);
         camera.set_follow_direction(FollowDirection::Horizontal);
+        let camera_effects = CameraEffects::new(ScreenShake::new(
+            CAMERA_SHAKE_OFFSET,
+            CAMERA_SHAKE_FREQUENCY,
+            CAMERA_SHAKE_DECAY,
+        ));
 
-        let cave = TilemapCave::new(ctx);
+        let cave = TilemapCave::new(ctx, &mut assets)?;
+        drop(preloaded);
+        for error in assets.take_errors() {
+            println!("{}", error);
+        }
 
-        Self {
+        let mut light_map = LightMap::new(AMBIENT_LIGHT);
+        light_map.add_light(PointLight::new(SPAWN_POSITION, TORCH_RADIUS, TORCH_COLOR));
+
+        let post_process = post_process_chain();
+
+        Ok(Self {
             camera,
+            camera_effects,
             world,
             cave,
             player,
+            pixel_perfect: PixelPerfect::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT, PIXELS_PER_UNIT),
+            pixel_perfect_enabled: false,
+            light_map,
+            lighting_enabled: true,
+            post_process,
+            hud: Hud::new(),
+            paused: false,
+            best_distance: 0.0,
+            debug: DebugOverlay::new(),
+            contacts: Vec::new(),
+            sprite_batches: SpriteBatches::new(),
+            movement_config: MovementConfig::new(MOVEMENT_CONFIG),
+            assets,
+            scaler: ScreenScaler::new(
+                ScalingPolicy::Letterbox(SCREEN_WIDTH / SCREEN_HEIGHT),
+                SCREEN_WIDTH,
+                SCREEN_HEIGHT,
+                DISTANCE,
+            ),
+            window_size: (SCREEN_WIDTH, SCREEN_HEIGHT),
+            fullscreen: false,
+        })
+    }
+
+    fn reload_assets(&mut self, ctx: &mut Context, deltatime: Duration) {
+        for event in self.assets.update(ctx, deltatime) {
+            match event {
+                AssetEvent::Reloaded(handle) => {
+                    self.cave.reload_asset(&self.assets, handle);
+                    if let Err(err) = self.player.reload_asset(ctx, &self.assets, handle) {
+                        println!("{}", err);
+                    }
+                }
+                AssetEvent::Failed(handle, err) => println!(
+                    "Failed to reload {}: {}",
+                    self.assets.path(handle).unwrap_or("asset"),
+                    err
+                ),
+            }
+        }
+    }
+
+    fn jump(&mut self) {
+        if self.player.can_jump || self.player.midair_jumps_left > 0 {
+            let position = self.player.controller.collider().position();
+            let label = if self.player.can_jump {
+                "jump"
+            } else {
+                "air jump"
+            };
+            let lifetime = Some(Duration::from_secs_f32(JUMP_MARKER_LIFETIME));
+            debug_draw::queue(
+                DebugShape::circle(position, JUMP_MARKER_RADIUS),
+                JUMP_MARKER_COLOR,
+                lifetime,
+            );
+            debug_draw::queue(
+                DebugShape::text(position, label),
+                JUMP_MARKER_COLOR,
+                lifetime,
+            );
+            self.player.controller.jump();
+            if let Some(jump_sound) = self.player.jump_sound.as_mut() {
+                if let Err(_) = jump_sound.play() {
+                    println!("Failed to play sound");
+                }
+            }
+
+            if self.player.can_jump {
+                self.player.can_jump = false;
+            } else {
+                self.player.midair_jumps_left -= 1;
+            }
+        }
+    }
+
+    fn toggle_pixel_perfect(&mut self, ctx: &mut Context) {
+        self.pixel_perfect_enabled = !self.pixel_perfect_enabled;
+        self.world.set_pixel_snap(false);
+        let (width, height) = self.window_size;
+        self.resize_event(ctx, width, height);
+    }
+
+    fn handle_tweak_input(&mut self, input: UiInput) {
+        if self.hud.tweaks.handle_input(input).is_some() {
+            let params = self.hud.movement_params(self.player.controller.params());
+            self.player.controller.set_params(params);
+        }
+    }
+
+    fn handle_menu_input(&mut self, ctx: &mut Context, input: UiInput) {
+        match self.hud.pause_menu.handle_input(input) {
+            Some(MenuEvent::Activated(MENU_RESUME)) | Some(MenuEvent::Cancelled) => {
+                self.paused = false;
+            }
+            Some(MenuEvent::Activated(MENU_LIGHTING)) => {
+                self.lighting_enabled = !self.lighting_enabled;
+            }
+            Some(MenuEvent::Activated(MENU_PIXEL_PERFECT)) => self.toggle_pixel_perfect(ctx),
+            Some(MenuEvent::Activated(MENU_QUIT)) => ggez::event::quit(ctx),
+            _ => (),
         }
     }
 }
@@ -350,19 +813,51 @@ impl EventHandler for EzPlatform {
         if repeat {
             return;
         }
+        if self.paused {
+            if let Some(input) = UiInput::from_keycode(keycode) {
+                self.handle_menu_input(ctx, input);
+            }
+            return;
+        }
+        if self.hud.tweaks_visible {
+            if let Some(input) = tweak_input(keycode) {
+                self.handle_tweak_input(input);
+                return;
+            }
+        }
         match keycode {
-            JUMP_KEY => {
-                if self.player.can_jump || self.player.midair_jumps_left > 0 {
-                    self.player.controller.jump();
-                    if let Err(_) = self.player.jump_sound.play() {
-                        println!("Failed to play sound");
-                    }
-
-                    if self.player.can_jump {
-                        self.player.can_jump = false;
-                    } else {
-                        self.player.midair_jumps_left -= 1;
-                    }
+            JUMP_KEY => self.jump(),
+            PAUSE_KEY => self.paused = true,
+            TWEAK_KEY => self.hud.tweaks_visible = !self.hud.tweaks_visible,
+            SAVE_PRESET_KEY => {
+                let params = *self.player.controller.params();
+                match self.movement_config.save(&params) {
+                    Ok(()) => println!("Saved movement preset to {}", MOVEMENT_CONFIG),
+                    Err(err) => println!("Failed to save movement preset: {}", err),
+                }
+            }
+            DEBUG_KEY => {
+                self.debug.toggle();
+                let enabled = self.debug.is_enabled();
+                debug_draw::with_debug_draw(|debug_draw| debug_draw.set_enabled(enabled));
+            }
+            LIGHTING_KEY => {
+                self.lighting_enabled = !self.lighting_enabled;
+            }
+            CRT_KEY => self.post_process.toggle(PostEffectKind::Crt),
+            VIGNETTE_KEY => self.post_process.toggle(PostEffectKind::Vignette),
+            COLOR_GRADE_KEY => self.post_process.toggle(PostEffectKind::ColorGrade),
+            PIXELATE_KEY => self.post_process.toggle(PostEffectKind::Pixelate),
+            PIXEL_PERFECT_KEY => self.toggle_pixel_perfect(ctx),
+            FULLSCREEN_KEY => {
+                self.fullscreen = !self.fullscreen;
+                let fullscreen_type = if self.fullscreen {
+                    FullscreenType::Desktop
+                } else {
+                    FullscreenType::Windowed
+                };
+                if graphics::set_fullscreen(ctx, fullscreen_type).is_err() {
+                    println!("Failed to toggle fullscreen");
                 }
             }
             QUIT_KEY => ggez::event::quit(ctx),
@@ -370,8 +865,40 @@ impl EventHandler for EzPlatform {
         }
     }
 
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, button: Button, _id: GamepadId) {
+        if self.paused {
+            if let Some(input) = UiInput::from_button(button) {
+                self.handle_menu_input(ctx, input);
+            }
+            return;
+        }
+        match button {
+            JUMP_BUTTON => self.jump(),
+            PAUSE_BUTTON => self.paused = true,
+            _ => (),
+        }
+    }
+
     fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
         let deltatime = timer::delta(ctx);
+        self.debug.record_frame(deltatime);
+        self.reload_assets(ctx, deltatime);
+        if self.paused {
+            return Ok(());
+        }
+        debug_draw::with_debug_draw(|debug_draw| debug_draw.update(deltatime));
+        if let Some(result) = self
+            .movement_config
+            .poll(deltatime, *self.player.controller.params())
+        {
+            match result {
+                Ok(params) => {
+                    self.player.controller.set_params(params);
+                    self.hud.set_movement_params(&params);
+                }
+                Err(err) => println!("{}", err),
+            }
+        }
         if keyboard::is_key_pressed(ctx, LEFT_KEY) {
             self.player.controller.move_left();
             self.player.orientation = -1;
@@ -382,10 +909,7 @@ impl EventHandler for EzPlatform {
             self.player.controller.stop();
         }
         if !keyboard::is_key_pressed(ctx, JUMP_KEY) {
-            let velocity = self.player.controller.collider_mut().velocity_mut();
-            if velocity.y > 0.0 {
-                velocity.y -= JUMP_DECAY * velocity.y * deltatime.as_secs_f32();
-            }
+            self.player.controller.release_jump(deltatime);
         }
         self.player.controller.update(deltatime);
 
@@ -405,10 +929,22 @@ impl EventHandler for EzPlatform {
             y: player_rect.y,
         });
         self.camera.update(deltatime);
-        self.world.look_at(self.camera.position());
+        self.camera_effects.update(deltatime);
+        self.camera_effects
+            .apply(&mut self.world, self.camera.position());
+        if self.pixel_perfect_enabled {
+            let snapped = self.pixel_perfect.snap(self.world.camera_position());
+            self.world.look_at(snapped);
+        }
 
+        let was_grounded = self.player.can_jump;
+        let fall_speed = -self.player.controller.collider().velocity().y;
         let collisions = self.cave.get_collisions(player_rect);
-        self.player
+        for wall in collisions.iter() {
+            debug_draw::rect(*wall, COLLISION_QUERY_COLOR);
+        }
+        self.contacts = self
+            .player
             .controller
             .collider_mut()
             .resolve_collisions(&collisions);
@@ -420,6 +956,30 @@ impl EventHandler for EzPlatform {
                 break;
             }
         }
+        if !was_grounded && self.player.can_jump && fall_speed > LANDING_SHAKE_SPEED {
+            self.camera_effects.add_trauma(LANDING_TRAUMA);
+            self.post_process.trigger_flash(
+                LANDING_FLASH_COLOR,
+                Duration::from_secs_f32(LANDING_FLASH_DURATION),
+            );
+        }
+        if !was_grounded && self.player.can_jump && fall_speed > LANDING_DUST_SPEED {
+            let position = self.player.controller.collider().position();
+            self.player.dust.set_position(Point2 {
+                x: position.x,
+                y: position.y - PLAYER_HEIGHT / 2.0,
+            });
+            self.player.dust.burst(LANDING_DUST_PARTICLES);
+        }
+        let colliders: Vec<&TilemapCollider> = self.cave.tilemap_colliders.iter().collect();
+        self.player
+            .dust
+            .update_with_colliders(deltatime, &colliders);
+        if let Some(torch) = self.light_map.light_mut(TORCH_LIGHT) {
+            torch.position = self.player.controller.collider().position();
+        }
+        self.light_map.occlude_tilemaps(&colliders);
+        self.post_process.update(deltatime);
 
         if player_rect.y < -DISTANCE - 0.5 {
             self.player.controller.collider_mut().position_mut().y = DISTANCE + 0.5;
@@ -428,23 +988,256 @@ impl EventHandler for EzPlatform {
             self.player.controller.collider_mut().position_mut().y = -DISTANCE - 0.5;
         }
 
-        self.player
-            .animator
-            .update(self.player.controller.collider().velocity(), deltatime);
+        let velocity = self.player.controller.collider().velocity();
+        let animator = &mut self.player.animator;
+        animator.set_float(SPEED_PARAM, velocity.x.abs());
+        animator.set_float(VERTICAL_VELOCITY_PARAM, velocity.y);
+        animator.set_bool(GROUNDED_PARAM, self.player.can_jump);
+        animator.update(deltatime);
+
+        let distance = self.player.controller.collider().position().x - SPAWN_POSITION.x;
+        self.best_distance = self.best_distance.max(distance);
+        self.hud
+            .update(self.best_distance, self.player.midair_jumps_left);
 
         Ok(())
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        graphics::clear(ctx, BG_COLOR);
+        if self.pixel_perfect_enabled {
+            let virtual_rect = Rect::new(
+                0.0,
+                0.0,
+                self.pixel_perfect.virtual_width() as f32,
+                self.pixel_perfect.virtual_height() as f32,
+            );
+            if self.lighting_enabled {
+                self.light_map.render(ctx, &self.world, virtual_rect)?;
+            }
+            let (cave, player, world) = (&self.cave, &self.player, &self.world);
+            let light_map = Some(&self.light_map).filter(|_| self.lighting_enabled);
+            let batches = &mut self.sprite_batches;
+            self.pixel_perfect.render(ctx, BG_COLOR, |ctx| {
+                draw_scene(ctx, world, cave, player, batches)?;
+                match light_map {
+                    Some(light_map) => light_map.apply(ctx, virtual_rect),
+                    None => Ok(()),
+                }
+            })?;
+            let pixel_perfect = &self.pixel_perfect;
+            self.post_process
+                .draw(ctx, |ctx| pixel_perfect.present(ctx))?;
+            if self.debug.is_enabled() {
+                let (debug, camera, contacts) = (&self.debug, &self.camera, &self.contacts);
+                self.pixel_perfect.draw_overlay(ctx, |ctx| {
+                    draw_debug(ctx, world, debug, camera, cave, player, contacts)
+                })?;
+            }
+        } else {
+            let screen_rect = graphics::screen_coordinates(ctx);
+            if self.lighting_enabled {
+                self.light_map.render(ctx, &self.world, screen_rect)?;
+            }
+            let (cave, player, world) = (&self.cave, &self.player, &self.world);
+            let light_map = Some(&self.light_map).filter(|_| self.lighting_enabled);
+            let batches = &mut self.sprite_batches;
+            self.post_process.draw(ctx, |ctx| {
+                graphics::clear(ctx, BG_COLOR);
+                draw_scene(ctx, world, cave, player, batches)?;
+                match light_map {
+                    Some(light_map) => light_map.apply(ctx, screen_rect),
+                    None => Ok(()),
+                }
+            })?;
+            if self.debug.is_enabled() {
+                draw_debug(
+                    ctx,
+                    &self.world,
+                    &self.debug,
+                    &self.camera,
+                    &self.cave,
+                    &self.player,
+                    &self.contacts,
+                )?;
+            }
 
-        self.cave.draw(ctx, &self.world)?;
+            let (width, height) = self.window_size;
+            self.scaler
+                .draw_letterbox(ctx, width, height, LETTERBOX_COLOR)?;
+        }
 
-        self.player.draw(ctx, &self.world)?;
+        self.hud.draw(ctx, self.paused)?;
+        if self.debug.is_enabled() {
+            let debug = &self.debug;
+            self.hud.layer.draw(ctx, |ctx, screen| {
+                let graph_rect =
+                    Anchor::BottomLeft.place(screen, Vector2 { x: 0.0, y: 0.0 }, FRAME_GRAPH_SIZE);
+                debug.draw_frame_graph(ctx, graph_rect)
+            })?;
+        }
 
         graphics::present(ctx)?;
         Ok(())
     }
+
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        self.window_size = (width, height);
+        self.hud.layer.resize(width, height);
+        let result = if self.pixel_perfect_enabled {
+            self.pixel_perfect.configure(&mut self.world);
+            graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, width, height))
+        } else {
+            self.scaler.resize(ctx, &mut self.world, width, height)
+        };
+        if result.is_err() {
+            println!("Failed to resize screen");
+        }
+    }
+}
+
+fn movement_params() -> MovementParams {
+    MovementParams {
+        move_force: MOVE_FORCE,
+        jump_impulse: JUMP_IMPULSE,
+        max_speed: MAX_SPEED,
+        move_speed_decay: MOVE_SPEED_DECAY,
+        jump_decay: JUMP_DECAY,
+        gravity_acceleration: GRAVITY_ACCELERATION,
+    }
+}
+
+fn movement_tweaks(params: &MovementParams) -> TweakPanel {
+    let tweaks = MOVEMENT_TWEAK_STEPS
+        .iter()
+        .map(|(field, step)| {
+            Tweak::new(field, params.get(field).unwrap_or(0.0), *step).with_range(0.0, f32::MAX)
+        })
+        .collect();
+    TweakPanel::new(tweaks)
+}
+
+fn tweak_input(keycode: KeyCode) -> Option<UiInput> {
+    match keycode {
+        TWEAK_PREVIOUS_KEY => Some(UiInput::Up),
+        TWEAK_NEXT_KEY => Some(UiInput::Down),
+        TWEAK_DECREASE_KEY => Some(UiInput::Left),
+        TWEAK_INCREASE_KEY => Some(UiInput::Right),
+        _ => None,
+    }
+}
+
+fn dust_settings() -> EmitterSettings {
+    EmitterSettings {
+        rate: 0.0,
+        lifetime: (0.3, 0.6),
+        speed: (1.5, 3.5),
+        direction: PI / 2.0,
+        spread: PI / 2.5,
+        gravity: Vector2 { x: 0.0, y: -6.0 },
+        damping: 3.0,
+        start_color: DUST_COLOR,
+        end_color: Color::new(DUST_COLOR.r, DUST_COLOR.g, DUST_COLOR.b, 0.0),
+        start_size: 0.15,
+        end_size: 0.3,
+        ..EmitterSettings::default()
+    }
+}
+
+fn asset_manifest() -> AssetManifest {
+    AssetManifest::new()
+        .image(PLAYER_IDLE)
+        .image(PLAYER_JUMP)
+        .image(PLAYER_FALL)
+        .image(PLAYER_WALK)
+        .image(GROUND_TILES)
+        .sound(JUMP_SOUND)
+        .level(CAVE_LEVEL)
+}
+
+fn draw_loading_screen(ctx: &mut Context, progress: &LoadProgress) -> GameResult {
+    let mut bar = ProgressBar::new(
+        progress.total as f32,
+        LOADING_BAR_SIZE.0,
+        LOADING_BAR_SIZE.1,
+    )
+    .colors(JUMP_BAR_COLOR, Color::new(0.0, 0.0, 0.0, 0.5))
+    .border(HUD_COLOR, 1.0)
+    .placement(Anchor::Center, Vector2 { x: 0.0, y: 0.0 });
+    bar.set_value(progress.loaded as f32);
+    graphics::clear(ctx, BG_COLOR);
+    UiLayer::new(SCREEN_WIDTH, SCREEN_HEIGHT).draw(ctx, |ctx, rect| bar.draw_ui(ctx, rect))?;
+    graphics::present(ctx)
+}
+
+fn post_process_chain() -> PostProcessChain {
+    let mut chain = PostProcessChain::new();
+    let color_grade = chain.add_effect(PostEffect::ColorGrade {
+        tint: Color::new(1.0, 0.95, 0.85, 1.0),
+        saturation: 0.8,
+        contrast: 1.1,
+        brightness: 0.0,
+    });
+    let pixelate = chain.add_effect(PostEffect::Pixelate {
+        pixel_size: PIXELATE_SIZE,
+    });
+    let crt = chain.add_effect(PostEffect::Crt {
+        scanlines: 0.3,
+        curvature: 0.1,
+        flicker: 0.02,
+    });
+    chain.add_effect(PostEffect::Vignette {
+        strength: 0.6,
+        radius: 0.75,
+        softness: 0.45,
+    });
+    chain.add_effect(PostEffect::Flash {
+        color: LANDING_FLASH_COLOR,
+        amount: 0.0,
+    });
+    for index in [color_grade, pixelate, crt].iter() {
+        chain.set_enabled(*index, false);
+    }
+    chain
+}
+
+fn draw_debug(
+    ctx: &mut Context,
+    world: &World,
+    debug: &DebugOverlay,
+    camera: &SmoothCamera,
+    cave: &TilemapCave,
+    player: &Player,
+    contacts: &[Contact],
+) -> GameResult {
+    let chunks: Vec<Rect> = cave
+        .tilemap_colliders
+        .iter()
+        .map(TilemapCollider::bounds)
+        .collect();
+    debug.draw_chunks(ctx, world, &chunks)?;
+    debug.draw_camera(ctx, world, camera.destination(), camera.dead_zone_rect())?;
+    debug.draw_collider(ctx, world, player.controller.collider())?;
+    debug.draw_ground_checks(ctx, world, &player.controller.ground_check_points())?;
+    debug.draw_contacts(ctx, world, contacts)?;
+    debug_draw::with_debug_draw(|debug_draw| debug_draw.draw(ctx, world))
+}
+
+fn draw_scene(
+    ctx: &mut Context,
+    world: &World,
+    cave: &TilemapCave,
+    player: &Player,
+    batches: &mut SpriteBatches,
+) -> GameResult {
+    let mut queue = RenderQueue::new();
+    cave.submit(&mut queue);
+    queue.submit_fn(PLAYER_LAYER, SortKey::Z(0), |ctx, world| {
+        player.draw(ctx, world)
+    });
+    player
+        .dust
+        .submit(&mut queue, PARTICLE_LAYER, SortKey::Z(0));
+    queue.flush_with(ctx, world, batches)
 }
 
 type TileHashmap = HashMap<(bool, bool, bool, bool), u32>;
@@ -535,21 +1328,49 @@ fn generate_ground_template(
         }
     }
 
-    for col in 0..width {
-        for row in 0..height {
-            if template[row as usize][col as usize] == E {
+    template.reverse();
+    autotile(&mut template, map);
+    template
+}
+
+fn level_template(level: &Level, map: &TileHashmap) -> GameResult<Vec<Vec<u32>>> {
+    if level.width() != TEMPLATE_WIDTH as usize || level.height() != TEMPLATE_HEIGHT as usize {
+        return Err(GameError::ResourceLoadError(format!(
+            "Cave level must be {}x{} tiles, got {}x{}",
+            TEMPLATE_WIDTH,
+            TEMPLATE_HEIGHT,
+            level.width(),
+            level.height()
+        )));
+    }
+    let mut template: Vec<Vec<u32>> = level
+        .tiles()
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|tile| if *tile == E { E } else { N })
+                .collect()
+        })
+        .collect();
+    autotile(&mut template, map);
+    Ok(template)
+}
+
+fn autotile(template: &mut [Vec<u32>], map: &TileHashmap) {
+    let height = template.len();
+    for row in 0..height {
+        let width = template[row].len();
+        for col in 0..width {
+            if template[row][col] == E {
                 continue;
             }
             let sides = (
-                row + 1 < height && template[(row + 1) as usize][col as usize] == E,
-                row - 1 >= 0 && template[(row - 1) as usize][col as usize] == E,
-                col - 1 >= 0 && template[row as usize][(col - 1) as usize] == E,
-                col + 1 < width && template[row as usize][(col + 1) as usize] == E,
+                row > 0 && template[row - 1][col] == E,
+                row + 1 < height && template[row + 1][col] == E,
+                col > 0 && template[row][col - 1] == E,
+                col + 1 < width && template[row][col + 1] == E,
             );
-            template[row as usize][col as usize] = *map.get(&sides).unwrap();
+            template[row][col] = *map.get(&sides).unwrap();
         }
     }
-
-    template.reverse();
-    template
 }
@@ -1,10 +1,22 @@
 pub mod animation;
+pub mod aseprite;
+pub mod assets;
+pub mod atlas;
 pub mod camera;
 pub mod collision;
 pub mod debug;
+pub mod debug_draw;
 pub mod game;
+pub mod lighting;
 pub mod movement;
+pub mod particles;
 pub mod physics;
+pub mod pixel_perfect;
+pub mod post_process;
+pub mod render_queue;
 pub mod rendering;
+pub mod scaling;
 pub mod tilemap;
+pub mod ui;
+pub mod viewport;
 pub mod world;
@@ -0,0 +1,379 @@
+use std::f32::consts::PI;
+
+use ggez::{
+    conf::NumSamples,
+    graphics::{self, BlendMode, Canvas, Color, DrawParam, Drawable, Mesh, Rect, Vertex},
+    mint::{Point2, Vector2},
+    Context, GameResult,
+};
+
+use crate::{collision::TilemapCollider, world::World};
+
+const DEFAULT_RAY_COUNT: u32 = 64;
+const CORNER_ANGLE_OFFSET: f32 = 0.0001;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointLight {
+    pub position: Point2<f32>,
+    pub radius: f32,
+    pub color: Color,
+    pub intensity: f32,
+}
+
+impl PointLight {
+    pub fn new(position: Point2<f32>, radius: f32, color: Color) -> Self {
+        Self {
+            position,
+            radius,
+            color,
+            intensity: 1.0,
+        }
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::new(
+            self.position.x,
+            self.position.y,
+            2.0 * self.radius,
+            2.0 * self.radius,
+        )
+    }
+
+    pub fn falloff(&self, point: Point2<f32>) -> f32 {
+        if self.radius <= 0.0 {
+            return 0.0;
+        }
+        let distance = (point.x - self.position.x).hypot(point.y - self.position.y);
+        (1.0 - distance / self.radius).clamp(0.0, 1.0) * self.intensity
+    }
+}
+
+pub struct LightMap {
+    ambient: Color,
+    lights: Vec<PointLight>,
+    occluders: Vec<Rect>,
+    ray_count: u32,
+    canvas: Option<Canvas>,
+}
+
+impl LightMap {
+    pub fn new(ambient_level: f32) -> Self {
+        let mut light_map = Self {
+            ambient: graphics::BLACK,
+            lights: Vec::new(),
+            occluders: Vec::new(),
+            ray_count: DEFAULT_RAY_COUNT,
+            canvas: None,
+        };
+        light_map.set_ambient_level(ambient_level);
+        light_map
+    }
+
+    pub fn set_ambient_level(&mut self, level: f32) {
+        let level = level.clamp(0.0, 1.0);
+        self.ambient = Color::new(level, level, level, 1.0);
+    }
+
+    pub fn set_ambient_color(&mut self, color: Color) {
+        self.ambient = color;
+    }
+
+    pub fn set_ray_count(&mut self, ray_count: u32) {
+        self.ray_count = ray_count.max(3);
+    }
+
+    pub fn add_light(&mut self, light: PointLight) -> usize {
+        self.lights.push(light);
+        self.lights.len() - 1
+    }
+
+    pub fn light_mut(&mut self, index: usize) -> Option<&mut PointLight> {
+        self.lights.get_mut(index)
+    }
+
+    pub fn clear_lights(&mut self) {
+        self.lights.clear();
+    }
+
+    pub fn set_occluders(&mut self, occluders: Vec<Rect>) {
+        self.occluders = occluders;
+    }
+
+    pub fn occlude_tilemaps(&mut self, colliders: &[&TilemapCollider]) {
+        let bounds = self
+            .lights
+            .iter()
+            .map(PointLight::bounds)
+            .reduce(|union, bounds| combine(&union, &bounds));
+        self.occluders = match bounds {
+            Some(bounds) => colliders
+                .iter()
+                .flat_map(|collider| collider.get_collision_lines(bounds))
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    pub fn light_occluders(&self, light: &PointLight) -> Vec<Rect> {
+        let bounds = light.bounds();
+        self.occluders
+            .iter()
+            .copied()
+            .filter(|occluder| overlaps(occluder, &bounds))
+            .collect()
+    }
+
+    pub fn light_polygon(&self, light: &PointLight) -> Vec<Point2<f32>> {
+        light_polygon(
+            light.position,
+            light.radius,
+            &self.light_occluders(light),
+            self.ray_count,
+        )
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, world: &World) -> GameResult {
+        let screen_rect = graphics::screen_coordinates(ctx);
+        self.render(ctx, world, screen_rect)?;
+        self.apply(ctx, screen_rect)
+    }
+
+    pub fn render(&mut self, ctx: &mut Context, world: &World, screen_rect: Rect) -> GameResult {
+        let width = screen_rect.w.abs().round().max(1.0) as u16;
+        let height = screen_rect.h.abs().round().max(1.0) as u16;
+        let needs_canvas = match &self.canvas {
+            Some(canvas) => canvas.image().width() != width || canvas.image().height() != height,
+            None => true,
+        };
+        if needs_canvas {
+            let mut canvas = Canvas::new(ctx, width, height, NumSamples::One)?;
+            canvas.set_blend_mode(Some(BlendMode::Multiply));
+            self.canvas = Some(canvas);
+        }
+
+        let meshes = self.light_meshes(ctx, world)?;
+        let canvas = self.canvas.as_ref().unwrap();
+        let screen_coordinates = graphics::screen_coordinates(ctx);
+        graphics::set_canvas(ctx, Some(canvas));
+        let result = draw_lights(ctx, &meshes, screen_rect, self.ambient);
+        graphics::set_canvas(ctx, None);
+        let restored = graphics::set_screen_coordinates(ctx, screen_coordinates);
+        result.and(restored)
+    }
+
+    pub fn apply(&self, ctx: &mut Context, screen_rect: Rect) -> GameResult {
+        let canvas = match &self.canvas {
+            Some(canvas) => canvas,
+            None => return Ok(()),
+        };
+        let scale = Vector2 {
+            x: screen_rect.w / canvas.image().width() as f32,
+            y: screen_rect.h / canvas.image().height() as f32,
+        };
+        graphics::draw(
+            ctx,
+            canvas,
+            DrawParam::default().dest(screen_rect.point()).scale(scale),
+        )
+    }
+
+    pub fn lights(&self) -> &[PointLight] {
+        &self.lights
+    }
+
+    pub fn occluders(&self) -> &[Rect] {
+        &self.occluders
+    }
+
+    pub fn ambient(&self) -> Color {
+        self.ambient
+    }
+
+    fn light_meshes(&self, ctx: &mut Context, world: &World) -> GameResult<Vec<Mesh>> {
+        let mut meshes = Vec::with_capacity(self.lights.len());
+        for light in self.lights.iter() {
+            let polygon = self.light_polygon(light);
+            if polygon.len() < 3 {
+                continue;
+            }
+            let vertex = |point: Point2<f32>, strength: f32| {
+                let screen_point = world.world_to_screen_pos(point);
+                Vertex {
+                    pos: [screen_point.x, screen_point.y],
+                    uv: [0.0, 0.0],
+                    color: [
+                        light.color.r * strength,
+                        light.color.g * strength,
+                        light.color.b * strength,
+                        1.0,
+                    ],
+                }
+            };
+            let mut vertices = vec![vertex(light.position, light.intensity)];
+            vertices.extend(
+                polygon
+                    .iter()
+                    .map(|point| vertex(*point, light.falloff(*point))),
+            );
+            let rim = polygon.len() as u32;
+            let indices: Vec<u32> = (0..rim)
+                .flat_map(|index| vec![0, index + 1, (index + 1) % rim + 1])
+                .collect();
+            meshes.push(Mesh::from_raw(ctx, &vertices, &indices, None)?);
+        }
+        Ok(meshes)
+    }
+}
+
+fn draw_lights(
+    ctx: &mut Context,
+    meshes: &[Mesh],
+    screen_rect: Rect,
+    ambient: Color,
+) -> GameResult {
+    graphics::set_screen_coordinates(ctx, screen_rect)?;
+    graphics::clear(ctx, ambient);
+    graphics::set_blend_mode(ctx, BlendMode::Add)?;
+    let result = meshes
+        .iter()
+        .try_for_each(|mesh| graphics::draw(ctx, mesh, DrawParam::default()));
+    let restored = graphics::set_blend_mode(ctx, BlendMode::Alpha);
+    result.and(restored)
+}
+
+pub fn light_polygon(
+    origin: Point2<f32>,
+    radius: f32,
+    occluders: &[Rect],
+    ray_count: u32,
+) -> Vec<Point2<f32>> {
+    let occluders: Vec<Rect> = occluders
+        .iter()
+        .copied()
+        .filter(|rect| !contains(rect, origin))
+        .collect();
+    let edges: Vec<(Point2<f32>, Point2<f32>)> = occluders.iter().flat_map(rect_edges).collect();
+
+    let mut angles: Vec<f32> = (0..ray_count)
+        .map(|index| 2.0 * PI * index as f32 / ray_count as f32)
+        .collect();
+    for rect in occluders.iter() {
+        for corner in rect_corners(rect).iter() {
+            let (dx, dy) = (corner.x - origin.x, corner.y - origin.y);
+            if dx.hypot(dy) > radius * 1.5 {
+                continue;
+            }
+            let angle = dy.atan2(dx);
+            angles.extend_from_slice(&[
+                angle - CORNER_ANGLE_OFFSET,
+                angle,
+                angle + CORNER_ANGLE_OFFSET,
+            ]);
+        }
+    }
+    let mut angles: Vec<f32> = angles
+        .into_iter()
+        .map(|angle| angle.rem_euclid(2.0 * PI))
+        .collect();
+    angles.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    angles.dedup();
+
+    angles
+        .into_iter()
+        .map(|angle| {
+            let direction = Vector2 {
+                x: angle.cos(),
+                y: angle.sin(),
+            };
+            let distance = edges
+                .iter()
+                .filter_map(|(a, b)| ray_segment_intersection(origin, direction, *a, *b))
+                .fold(radius, f32::min);
+            Point2 {
+                x: origin.x + direction.x * distance,
+                y: origin.y + direction.y * distance,
+            }
+        })
+        .collect()
+}
+
+fn ray_segment_intersection(
+    origin: Point2<f32>,
+    direction: Vector2<f32>,
+    a: Point2<f32>,
+    b: Point2<f32>,
+) -> Option<f32> {
+    let edge = Vector2 {
+        x: b.x - a.x,
+        y: b.y - a.y,
+    };
+    let denominator = cross(direction, edge);
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let to_start = Vector2 {
+        x: a.x - origin.x,
+        y: a.y - origin.y,
+    };
+    let t = cross(to_start, edge) / denominator;
+    let u = cross(to_start, direction) / denominator;
+    if t >= 0.0 && (0.0..=1.0).contains(&u) {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+fn cross(a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn overlaps(a: &Rect, b: &Rect) -> bool {
+    (a.x - b.x).abs() <= (a.w + b.w) / 2.0 && (a.y - b.y).abs() <= (a.h + b.h) / 2.0
+}
+
+fn combine(a: &Rect, b: &Rect) -> Rect {
+    let left = (a.x - a.w / 2.0).min(b.x - b.w / 2.0);
+    let right = (a.x + a.w / 2.0).max(b.x + b.w / 2.0);
+    let bottom = (a.y - a.h / 2.0).min(b.y - b.h / 2.0);
+    let top = (a.y + a.h / 2.0).max(b.y + b.h / 2.0);
+    Rect::new(
+        (left + right) / 2.0,
+        (bottom + top) / 2.0,
+        right - left,
+        top - bottom,
+    )
+}
+
+fn contains(rect: &Rect, point: Point2<f32>) -> bool {
+    (point.x - rect.x).abs() < rect.w / 2.0 && (point.y - rect.y).abs() < rect.h / 2.0
+}
+
+fn rect_corners(rect: &Rect) -> [Point2<f32>; 4] {
+    let (half_w, half_h) = (rect.w / 2.0, rect.h / 2.0);
+    [
+        Point2 {
+            x: rect.x - half_w,
+            y: rect.y - half_h,
+        },
+        Point2 {
+            x: rect.x + half_w,
+            y: rect.y - half_h,
+        },
+        Point2 {
+            x: rect.x + half_w,
+            y: rect.y + half_h,
+        },
+        Point2 {
+            x: rect.x - half_w,
+            y: rect.y + half_h,
+        },
+    ]
+}
+
+fn rect_edges(rect: &Rect) -> Vec<(Point2<f32>, Point2<f32>)> {
+    let corners = rect_corners(rect);
+    (0..4)
+        .map(|index| (corners[index], corners[(index + 1) % 4]))
+        .collect()
+}
@@ -3,11 +3,15 @@ use ggez::{event, ContextBuilder, GameResult};
 
 fn main() -> GameResult {
     let (mut ctx, mut event_loop) = ContextBuilder::new("EzPlatform", "Plamen Nikolov")
-        .window_mode(ggez::conf::WindowMode::default().dimensions(SCREEN_WIDTH, SCREEN_HEIGHT))
+        .window_mode(
+            ggez::conf::WindowMode::default()
+                .dimensions(SCREEN_WIDTH, SCREEN_HEIGHT)
+                .resizable(true),
+        )
         .build()
         .unwrap();
 
-    let mut ez_platform = EzPlatform::new(&mut ctx);
+    let mut ez_platform = EzPlatform::new(&mut ctx)?;
 
     event::run(&mut ctx, &mut event_loop, &mut ez_platform)
 }
@@ -1,51 +1,221 @@
-use std::time::Duration;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use ggez::{
     graphics::Rect,
     mint::{Point2, Vector2},
+    GameError, GameResult,
 };
+use serde_json::{Map, Value};
+
+use crate::{assets::FileWatcher, collision::DynamicCollider, physics::PhysicsObject};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MovementParams {
+    pub move_force: f32,
+    pub jump_impulse: f32,
+    pub max_speed: f32,
+    pub move_speed_decay: f32,
+    pub jump_decay: f32,
+    pub gravity_acceleration: f32,
+}
+
+impl MovementParams {
+    pub const FIELDS: [&'static str; 6] = [
+        "move_force",
+        "jump_impulse",
+        "max_speed",
+        "move_speed_decay",
+        "jump_decay",
+        "gravity_acceleration",
+    ];
+
+    pub fn get(&self, field: &str) -> Option<f32> {
+        match field {
+            "move_force" => Some(self.move_force),
+            "jump_impulse" => Some(self.jump_impulse),
+            "max_speed" => Some(self.max_speed),
+            "move_speed_decay" => Some(self.move_speed_decay),
+            "jump_decay" => Some(self.jump_decay),
+            "gravity_acceleration" => Some(self.gravity_acceleration),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, field: &str, value: f32) -> bool {
+        let target = match field {
+            "move_force" => &mut self.move_force,
+            "jump_impulse" => &mut self.jump_impulse,
+            "max_speed" => &mut self.max_speed,
+            "move_speed_decay" => &mut self.move_speed_decay,
+            "jump_decay" => &mut self.jump_decay,
+            "gravity_acceleration" => &mut self.gravity_acceleration,
+            _ => return false,
+        };
+        *target = value;
+        true
+    }
+
+    pub fn from_json(json: &str, defaults: MovementParams) -> GameResult<Self> {
+        let root: Value = serde_json::from_str(json)
+            .map_err(|err| config_error(format!("invalid json: {}", err)))?;
+        let fields = root
+            .as_object()
+            .ok_or_else(|| config_error("expected an object".to_string()))?;
+        let mut params = defaults;
+        for (field, value) in fields.iter() {
+            let value = value
+                .as_f64()
+                .ok_or_else(|| config_error(format!("{} is not a number", field)))?;
+            if !params.set(field, value as f32) {
+                return Err(config_error(format!("unknown field {}", field)));
+            }
+        }
+        Ok(params)
+    }
 
-use crate::{collision::DynamicCollider, physics::PhysicsObject};
+    pub fn to_json(&self) -> String {
+        let mut fields = Map::new();
+        for field in Self::FIELDS.iter() {
+            if let Some(value) = self.get(field) {
+                let value = value.to_string().parse().unwrap_or(value as f64);
+                fields.insert(field.to_string(), Value::from(value));
+            }
+        }
+        serde_json::to_string_pretty(&Value::Object(fields)).unwrap_or_default()
+    }
+
+    pub fn save_preset<P: AsRef<Path>>(&self, path: P) -> GameResult {
+        fs::write(path, self.to_json() + "\n")?;
+        Ok(())
+    }
+
+    pub fn load_preset<P: AsRef<Path>>(path: P, defaults: MovementParams) -> GameResult<Self> {
+        Self::from_json(&fs::read_to_string(path)?, defaults)
+    }
+}
+
+pub struct MovementConfig {
+    path: PathBuf,
+    watcher: FileWatcher<()>,
+}
+
+impl MovementConfig {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        let mut watcher = FileWatcher::new();
+        watcher.watch((), &path);
+        watcher.invalidate(());
+        Self { path, watcher }
+    }
+
+    pub fn set_poll_interval(&mut self, poll_interval: Duration) {
+        self.watcher.set_poll_interval(poll_interval);
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn poll(
+        &mut self,
+        deltatime: Duration,
+        current: MovementParams,
+    ) -> Option<GameResult<MovementParams>> {
+        if self.watcher.poll(deltatime).is_empty() {
+            return None;
+        }
+        Some(MovementParams::load_preset(&self.path, current))
+    }
+
+    pub fn reload_if_changed(
+        &mut self,
+        current: MovementParams,
+    ) -> Option<GameResult<MovementParams>> {
+        if self.watcher.changed().is_empty() {
+            return None;
+        }
+        Some(MovementParams::load_preset(&self.path, current))
+    }
+
+    pub fn save(&mut self, params: &MovementParams) -> GameResult {
+        params.save_preset(&self.path)?;
+        self.watcher.watch((), &self.path);
+        Ok(())
+    }
+}
+
+fn config_error(message: String) -> GameError {
+    GameError::ResourceLoadError(format!("Failed to parse movement config: {}", message))
+}
 
 pub struct MovementController {
     body: DynamicCollider,
-    move_force: f32,
-    jump_impulse: f32,
-    max_speed: f32,
-    move_speed_decay: f32,
-    gravity_acceleration: f32,
+    params: MovementParams,
     horizontal_force: f32,
     ground_check_offsets: Vec<Vector2<f32>>,
 }
 
 impl MovementController {
+    #[allow(clippy::too_many_arguments)]
     pub fn from_components(
         body: DynamicCollider,
         move_force: f32,
         jump_impulse: f32,
         max_speed: f32,
         move_speed_decay: f32,
+        jump_decay: f32,
         gravity_acceleration: f32,
         ground_check_offsets: &[Vector2<f32>],
+    ) -> Self {
+        Self::from_params(
+            body,
+            MovementParams {
+                move_force,
+                jump_impulse,
+                max_speed,
+                move_speed_decay,
+                jump_decay,
+                gravity_acceleration,
+            },
+            ground_check_offsets,
+        )
+    }
+
+    pub fn from_params(
+        body: DynamicCollider,
+        params: MovementParams,
+        ground_check_offsets: &[Vector2<f32>],
     ) -> Self {
         MovementController {
             body,
-            move_force,
-            jump_impulse,
-            max_speed,
-            move_speed_decay,
-            gravity_acceleration,
+            params,
             horizontal_force: 0.0,
             ground_check_offsets: ground_check_offsets.into(),
         }
     }
 
+    pub fn params(&self) -> &MovementParams {
+        &self.params
+    }
+
+    pub fn set_params(&mut self, params: MovementParams) {
+        let direction = self.horizontal_force.signum();
+        self.params = params;
+        if self.horizontal_force != 0.0 {
+            self.horizontal_force = direction * params.move_force;
+        }
+    }
+
     pub fn move_left(&mut self) {
-        self.horizontal_force = -self.move_force;
+        self.horizontal_force = -self.params.move_force;
     }
 
     pub fn move_right(&mut self) {
-        self.horizontal_force = self.move_force;
+        self.horizontal_force = self.params.move_force;
     }
 
     pub fn stop(&mut self) {
@@ -55,10 +225,17 @@ impl MovementController {
     pub fn jump(&mut self) {
         self.body.set_velocity(Vector2 {
             x: self.body.velocity().x,
-            y: self.jump_impulse,
+            y: self.params.jump_impulse,
         });
     }
 
+    pub fn release_jump(&mut self, deltatime: Duration) {
+        let velocity = self.body.velocity_mut();
+        if velocity.y > 0.0 {
+            velocity.y -= self.params.jump_decay * velocity.y * deltatime.as_secs_f32();
+        }
+    }
+
     pub fn collider(&self) -> &DynamicCollider {
         &self.body
     }
@@ -86,26 +263,26 @@ impl MovementController {
     pub fn update(&mut self, deltatime: Duration) {
         self.body.set_force(Vector2 {
             x: self.horizontal_force,
-            y: -self.gravity_acceleration * self.body.mass(),
+            y: -self.params.gravity_acceleration * self.body.mass(),
         });
         self.body.update(deltatime);
 
-        if self.body.velocity().x > self.max_speed {
-            self.body.velocity_mut().x = self.max_speed;
-        } else if self.body.velocity().x < -self.max_speed {
-            self.body.velocity_mut().x = -self.max_speed;
+        if self.body.velocity().x > self.params.max_speed {
+            self.body.velocity_mut().x = self.params.max_speed;
+        } else if self.body.velocity().x < -self.params.max_speed {
+            self.body.velocity_mut().x = -self.params.max_speed;
         }
 
         let deltaseconds = deltatime.as_secs_f32();
         if self.horizontal_force.abs() < 0.01
             || self.horizontal_force * self.body.velocity().x < 0.0
         {
-            if self.body.velocity().x.abs() < self.max_speed / 20.0 {
+            if self.body.velocity().x.abs() < self.params.max_speed / 20.0 {
                 self.body.velocity_mut().x = 0.0;
             } else if self.body.velocity().x > 0.0 {
-                self.body.velocity_mut().x -= self.move_speed_decay * deltaseconds;
+                self.body.velocity_mut().x -= self.params.move_speed_decay * deltaseconds;
             } else {
-                self.body.velocity_mut().x += self.move_speed_decay * deltaseconds;
+                self.body.velocity_mut().x += self.params.move_speed_decay * deltaseconds;
             }
         }
     }
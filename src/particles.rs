@@ -0,0 +1,318 @@
+use std::{f32::consts::PI, time::Duration};
+
+use ggez::{
+    graphics::{self, spritebatch::SpriteBatch, Color, DrawMode, DrawParam, MeshBuilder, Rect},
+    mint::{Point2, Vector2},
+    Context, GameResult,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    collision::TilemapCollider,
+    render_queue::{RenderQueue, SortKey},
+    rendering::{SpriteSheet, WorldDrawParams},
+    world::World,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EmitterSettings {
+    pub rate: f32,
+    pub max_particles: usize,
+    pub lifetime: (f32, f32),
+    pub speed: (f32, f32),
+    pub direction: f32,
+    pub spread: f32,
+    pub gravity: Vector2<f32>,
+    pub damping: f32,
+    pub start_color: Color,
+    pub end_color: Color,
+    pub start_size: f32,
+    pub end_size: f32,
+    pub bounce: f32,
+}
+
+impl Default for EmitterSettings {
+    fn default() -> Self {
+        Self {
+            rate: 10.0,
+            max_particles: 256,
+            lifetime: (0.5, 1.0),
+            speed: (1.0, 2.0),
+            direction: PI / 2.0,
+            spread: PI / 8.0,
+            gravity: Vector2 { x: 0.0, y: 0.0 },
+            damping: 0.0,
+            start_color: graphics::WHITE,
+            end_color: Color::new(1.0, 1.0, 1.0, 0.0),
+            start_size: 0.2,
+            end_size: 0.2,
+            bounce: 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Particle {
+    pub position: Point2<f32>,
+    pub velocity: Vector2<f32>,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+impl Particle {
+    pub fn progress(&self) -> f32 {
+        if self.lifetime <= 0.0 {
+            return 1.0;
+        }
+        (self.age / self.lifetime).clamp(0.0, 1.0)
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+}
+
+pub struct ParticleEmitter {
+    settings: EmitterSettings,
+    position: Point2<f32>,
+    particles: Vec<Particle>,
+    spawn_accumulator: f32,
+    emitting: bool,
+    sprites: Option<SpriteSheet>,
+    rng: StdRng,
+}
+
+impl ParticleEmitter {
+    pub fn new(settings: EmitterSettings, position: Point2<f32>) -> Self {
+        Self {
+            settings,
+            position,
+            particles: Vec::new(),
+            spawn_accumulator: 0.0,
+            emitting: true,
+            sprites: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn set_sprites(&mut self, sprites: SpriteSheet) {
+        self.sprites = Some(sprites);
+    }
+
+    pub fn set_position(&mut self, position: Point2<f32>) {
+        self.position = position;
+    }
+
+    pub fn set_emitting(&mut self, emitting: bool) {
+        self.emitting = emitting;
+        if !emitting {
+            self.spawn_accumulator = 0.0;
+        }
+    }
+
+    pub fn settings_mut(&mut self) -> &mut EmitterSettings {
+        &mut self.settings
+    }
+
+    pub fn burst(&mut self, count: usize) {
+        for _ in 0..count {
+            self.spawn();
+        }
+    }
+
+    pub fn update(&mut self, deltatime: Duration) {
+        self.update_with_colliders(deltatime, &[]);
+    }
+
+    pub fn update_with_colliders(&mut self, deltatime: Duration, colliders: &[&TilemapCollider]) {
+        let dt = deltatime.as_secs_f32();
+        if self.emitting && self.settings.rate > 0.0 {
+            self.spawn_accumulator += dt * self.settings.rate;
+            while self.spawn_accumulator >= 1.0 {
+                self.spawn_accumulator -= 1.0;
+                self.spawn();
+            }
+        }
+
+        let settings = self.settings;
+        let collides = |point: Point2<f32>| {
+            colliders
+                .iter()
+                .any(|collider| collider.check_collision(point))
+        };
+        for particle in self.particles.iter_mut() {
+            particle.age += dt;
+            let damping = (1.0 - settings.damping * dt).max(0.0);
+            particle.velocity.x = (particle.velocity.x + settings.gravity.x * dt) * damping;
+            particle.velocity.y = (particle.velocity.y + settings.gravity.y * dt) * damping;
+
+            let next_x = Point2 {
+                x: particle.position.x + particle.velocity.x * dt,
+                y: particle.position.y,
+            };
+            if collides(next_x) {
+                particle.velocity.x = -particle.velocity.x * settings.bounce;
+            } else {
+                particle.position = next_x;
+            }
+            let next_y = Point2 {
+                x: particle.position.x,
+                y: particle.position.y + particle.velocity.y * dt,
+            };
+            if collides(next_y) {
+                particle.velocity.y = -particle.velocity.y * settings.bounce;
+            } else {
+                particle.position = next_y;
+            }
+        }
+        self.particles.retain(Particle::is_alive);
+    }
+
+    pub fn color(&self, particle: &Particle) -> Color {
+        let t = particle.progress();
+        let (start, end) = (self.settings.start_color, self.settings.end_color);
+        Color::new(
+            lerp(start.r, end.r, t),
+            lerp(start.g, end.g, t),
+            lerp(start.b, end.b, t),
+            lerp(start.a, end.a, t),
+        )
+    }
+
+    pub fn size(&self, particle: &Particle) -> f32 {
+        lerp(
+            self.settings.start_size,
+            self.settings.end_size,
+            particle.progress(),
+        )
+    }
+
+    pub fn frame(&self, particle: &Particle) -> u32 {
+        match &self.sprites {
+            Some(sprites) if sprites.total_sprites() > 0 => {
+                let total_sprites = sprites.total_sprites();
+                ((particle.progress() * total_sprites as f32) as u32).min(total_sprites - 1)
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context, world: &World) -> GameResult {
+        if self.particles.is_empty() {
+            return Ok(());
+        }
+        match &self.sprites {
+            Some(sprites) => {
+                let mut batch = SpriteBatch::new((**sprites.image()).clone());
+                for particle in self.particles.iter() {
+                    if let Some(instance) = sprites.frame_instance(self.frame(particle)) {
+                        batch.add(instance.draw_param(
+                            world,
+                            self.particle_rect(particle),
+                            self.particle_params(particle),
+                        ));
+                    }
+                }
+                graphics::draw(ctx, &batch, DrawParam::default())
+            }
+            None => {
+                let mut builder = MeshBuilder::new();
+                for particle in self.particles.iter() {
+                    let rect = world.world_to_screen_rect(self.particle_rect(particle));
+                    builder.rectangle(
+                        DrawMode::fill(),
+                        Rect::new(rect.x - rect.w / 2.0, rect.y - rect.h / 2.0, rect.w, rect.h),
+                        self.color(particle),
+                    );
+                }
+                let mesh = builder.build(ctx)?;
+                graphics::draw(ctx, &mesh, DrawParam::default())
+            }
+        }
+    }
+
+    pub fn submit<'a>(&'a self, queue: &mut RenderQueue<'a>, layer: i32, sort_key: SortKey) {
+        match &self.sprites {
+            Some(sprites) => {
+                for particle in self.particles.iter() {
+                    if let Some(instance) = sprites.frame_instance(self.frame(particle)) {
+                        queue.submit_sprite(
+                            layer,
+                            sort_key,
+                            instance,
+                            self.particle_rect(particle),
+                            self.particle_params(particle),
+                        );
+                    }
+                }
+            }
+            None => {
+                queue.submit_fn(layer, sort_key, move |ctx, world| self.draw(ctx, world));
+            }
+        }
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    pub fn position(&self) -> Point2<f32> {
+        self.position
+    }
+
+    pub fn is_emitting(&self) -> bool {
+        self.emitting
+    }
+
+    pub fn settings(&self) -> &EmitterSettings {
+        &self.settings
+    }
+
+    fn spawn(&mut self) {
+        if self.particles.len() >= self.settings.max_particles {
+            return;
+        }
+        let angle = self.settings.direction
+            + random_in(&mut self.rng, -self.settings.spread, self.settings.spread);
+        let speed = random_in(&mut self.rng, self.settings.speed.0, self.settings.speed.1);
+        let lifetime = random_in(
+            &mut self.rng,
+            self.settings.lifetime.0,
+            self.settings.lifetime.1,
+        );
+        self.particles.push(Particle {
+            position: self.position,
+            velocity: Vector2 {
+                x: angle.cos() * speed,
+                y: angle.sin() * speed,
+            },
+            age: 0.0,
+            lifetime,
+        });
+    }
+
+    fn particle_rect(&self, particle: &Particle) -> Rect {
+        let size = self.size(particle);
+        Rect::new(particle.position.x, particle.position.y, size, size)
+    }
+
+    fn particle_params(&self, particle: &Particle) -> WorldDrawParams {
+        WorldDrawParams::new().tint(self.color(particle))
+    }
+}
+
+fn random_in(rng: &mut StdRng, min: f32, max: f32) -> f32 {
+    if max > min {
+        rng.gen_range(min..max)
+    } else {
+        min
+    }
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
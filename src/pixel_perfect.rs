@@ -0,0 +1,165 @@
+use ggez::{
+    conf::NumSamples,
+    graphics::{self, Canvas, Color, DrawParam, FilterMode, Rect},
+    mint::Point2,
+    Context, GameResult,
+};
+
+use crate::world::World;
+
+pub struct PixelPerfect {
+    virtual_width: u16,
+    virtual_height: u16,
+    pixels_per_unit: f32,
+    letterbox_color: Color,
+    canvas: Option<Canvas>,
+}
+
+impl PixelPerfect {
+    pub fn new(virtual_width: u16, virtual_height: u16, pixels_per_unit: f32) -> Self {
+        Self {
+            virtual_width,
+            virtual_height,
+            pixels_per_unit,
+            letterbox_color: graphics::BLACK,
+            canvas: None,
+        }
+    }
+
+    pub fn set_letterbox_color(&mut self, color: Color) {
+        self.letterbox_color = color;
+    }
+
+    pub fn distance(&self) -> f32 {
+        let shorter_side = self.virtual_width.min(self.virtual_height) as f32;
+        0.5 * shorter_side / self.pixels_per_unit
+    }
+
+    pub fn configure(&self, world: &mut World) {
+        world.set_screen_dims(self.virtual_width as f32, self.virtual_height as f32);
+        world.set_distance(self.distance());
+        world.set_pixel_snap(true);
+    }
+
+    pub fn scale_factor(&self, window_width: f32, window_height: f32) -> u32 {
+        let scale_x = window_width / self.virtual_width as f32;
+        let scale_y = window_height / self.virtual_height as f32;
+        (scale_x.min(scale_y).floor() as u32).max(1)
+    }
+
+    pub fn target_rect(&self, window_width: f32, window_height: f32) -> Rect {
+        let scale = self.scale_factor(window_width, window_height) as f32;
+        let w = self.virtual_width as f32 * scale;
+        let h = self.virtual_height as f32 * scale;
+        Rect::new(
+            (0.5 * (window_width - w)).floor(),
+            (0.5 * (window_height - h)).floor(),
+            w,
+            h,
+        )
+    }
+
+    pub fn snap(&self, position: Point2<f32>) -> Point2<f32> {
+        Point2 {
+            x: (position.x * self.pixels_per_unit).round() / self.pixels_per_unit,
+            y: (position.y * self.pixels_per_unit).round() / self.pixels_per_unit,
+        }
+    }
+
+    pub fn draw<F>(&mut self, ctx: &mut Context, background: Color, draw_fn: F) -> GameResult
+    where
+        F: FnOnce(&mut Context) -> GameResult,
+    {
+        self.render(ctx, background, draw_fn)?;
+        self.present(ctx)
+    }
+
+    pub fn render<F>(&mut self, ctx: &mut Context, background: Color, draw_fn: F) -> GameResult
+    where
+        F: FnOnce(&mut Context) -> GameResult,
+    {
+        if self.canvas.is_none() {
+            let mut canvas = Canvas::new(
+                ctx,
+                self.virtual_width,
+                self.virtual_height,
+                NumSamples::One,
+            )?;
+            canvas.set_filter(FilterMode::Nearest);
+            self.canvas = Some(canvas);
+        }
+        let canvas = self.canvas.as_ref().unwrap();
+
+        let screen_coordinates = graphics::screen_coordinates(ctx);
+        graphics::set_canvas(ctx, Some(canvas));
+        graphics::set_screen_coordinates(
+            ctx,
+            Rect::new(
+                0.0,
+                0.0,
+                self.virtual_width as f32,
+                self.virtual_height as f32,
+            ),
+        )?;
+        graphics::clear(ctx, background);
+        let result = draw_fn(ctx);
+        graphics::set_canvas(ctx, None);
+        graphics::set_screen_coordinates(ctx, screen_coordinates)?;
+        result
+    }
+
+    pub fn present(&self, ctx: &mut Context) -> GameResult {
+        let canvas = match &self.canvas {
+            Some(canvas) => canvas,
+            None => return Ok(()),
+        };
+        let screen_coordinates = graphics::screen_coordinates(ctx);
+        graphics::clear(ctx, self.letterbox_color);
+        let target = self.target_rect(screen_coordinates.w, screen_coordinates.h);
+        let scale = target.w / self.virtual_width as f32;
+        graphics::draw(
+            ctx,
+            canvas,
+            DrawParam::default()
+                .dest(Point2 {
+                    x: screen_coordinates.x + target.x,
+                    y: screen_coordinates.y + target.y,
+                })
+                .scale(Point2 { x: scale, y: scale }),
+        )
+    }
+
+    pub fn draw_overlay<F>(&self, ctx: &mut Context, draw_fn: F) -> GameResult
+    where
+        F: FnOnce(&mut Context) -> GameResult,
+    {
+        let screen_coordinates = graphics::screen_coordinates(ctx);
+        graphics::set_screen_coordinates(ctx, self.overlay_coordinates(screen_coordinates))?;
+        let result = draw_fn(ctx);
+        graphics::set_screen_coordinates(ctx, screen_coordinates)?;
+        result
+    }
+
+    pub fn overlay_coordinates(&self, screen_coordinates: Rect) -> Rect {
+        let target = self.target_rect(screen_coordinates.w, screen_coordinates.h);
+        let scale = target.w / self.virtual_width as f32;
+        Rect::new(
+            -target.x / scale,
+            -target.y / scale,
+            screen_coordinates.w / scale,
+            screen_coordinates.h / scale,
+        )
+    }
+
+    pub fn virtual_width(&self) -> u16 {
+        self.virtual_width
+    }
+
+    pub fn virtual_height(&self) -> u16 {
+        self.virtual_height
+    }
+
+    pub fn pixels_per_unit(&self) -> f32 {
+        self.pixels_per_unit
+    }
+}
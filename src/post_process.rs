@@ -0,0 +1,320 @@
+use std::{collections::HashMap, time::Duration};
+
+use gfx::{self, *};
+use ggez::{
+    conf::NumSamples,
+    graphics::{self, Canvas, Color, DrawParam, Shader},
+    mint::Vector2,
+    Context, GameResult,
+};
+
+const VERTEX_SHADER: &[u8] = include_bytes!("shaders/post_process.glslv");
+const CRT_SHADER: &[u8] = include_bytes!("shaders/crt.glslf");
+const VIGNETTE_SHADER: &[u8] = include_bytes!("shaders/vignette.glslf");
+const COLOR_GRADE_SHADER: &[u8] = include_bytes!("shaders/color_grade.glslf");
+const FLASH_SHADER: &[u8] = include_bytes!("shaders/flash.glslf");
+const PIXELATE_SHADER: &[u8] = include_bytes!("shaders/pixelate.glslf");
+const UNIFORM_BLOCK: &str = "PostProcess";
+
+gfx_defines! {
+    constant PostProcessUniforms {
+        resolution: [f32; 2] = "u_Resolution",
+        time: f32 = "u_Time",
+        strength: f32 = "u_Strength",
+        color: [f32; 4] = "u_Color",
+        params: [f32; 4] = "u_Params",
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PostEffectKind {
+    Crt,
+    Vignette,
+    ColorGrade,
+    Flash,
+    Pixelate,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PostEffect {
+    Crt {
+        scanlines: f32,
+        curvature: f32,
+        flicker: f32,
+    },
+    Vignette {
+        strength: f32,
+        radius: f32,
+        softness: f32,
+    },
+    ColorGrade {
+        tint: Color,
+        saturation: f32,
+        contrast: f32,
+        brightness: f32,
+    },
+    Flash {
+        color: Color,
+        amount: f32,
+    },
+    Pixelate {
+        pixel_size: f32,
+    },
+}
+
+impl PostEffect {
+    pub fn kind(&self) -> PostEffectKind {
+        match self {
+            PostEffect::Crt { .. } => PostEffectKind::Crt,
+            PostEffect::Vignette { .. } => PostEffectKind::Vignette,
+            PostEffect::ColorGrade { .. } => PostEffectKind::ColorGrade,
+            PostEffect::Flash { .. } => PostEffectKind::Flash,
+            PostEffect::Pixelate { .. } => PostEffectKind::Pixelate,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        match self {
+            PostEffect::Flash { amount, .. } => *amount > 0.0,
+            PostEffect::Pixelate { pixel_size } => *pixel_size > 1.0,
+            _ => true,
+        }
+    }
+
+    pub fn uniforms(&self, resolution: [f32; 2], time: f32) -> PostProcessUniforms {
+        let (strength, color, params) = match *self {
+            PostEffect::Crt {
+                scanlines,
+                curvature,
+                flicker,
+            } => (scanlines, graphics::WHITE, [curvature, flicker, 0.0, 0.0]),
+            PostEffect::Vignette {
+                strength,
+                radius,
+                softness,
+            } => (strength, graphics::WHITE, [radius, softness, 0.0, 0.0]),
+            PostEffect::ColorGrade {
+                tint,
+                saturation,
+                contrast,
+                brightness,
+            } => (1.0, tint, [saturation, contrast, brightness, 0.0]),
+            PostEffect::Flash { color, amount } => (amount, color, [0.0; 4]),
+            PostEffect::Pixelate { pixel_size } => {
+                (1.0, graphics::WHITE, [pixel_size, 0.0, 0.0, 0.0])
+            }
+        };
+        PostProcessUniforms {
+            resolution,
+            time,
+            strength,
+            color: [color.r, color.g, color.b, color.a],
+            params,
+        }
+    }
+}
+
+struct EffectSlot {
+    effect: PostEffect,
+    enabled: bool,
+}
+
+pub struct PostProcessChain {
+    effects: Vec<EffectSlot>,
+    shaders: HashMap<PostEffectKind, Shader<PostProcessUniforms>>,
+    canvases: Vec<Canvas>,
+    time: f32,
+    flash_duration: f32,
+}
+
+impl Default for PostProcessChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PostProcessChain {
+    pub fn new() -> Self {
+        Self {
+            effects: Vec::new(),
+            shaders: HashMap::new(),
+            canvases: Vec::new(),
+            time: 0.0,
+            flash_duration: 0.0,
+        }
+    }
+
+    pub fn add_effect(&mut self, effect: PostEffect) -> usize {
+        self.effects.push(EffectSlot {
+            effect,
+            enabled: true,
+        });
+        self.effects.len() - 1
+    }
+
+    pub fn effect(&self, index: usize) -> Option<&PostEffect> {
+        self.effects.get(index).map(|slot| &slot.effect)
+    }
+
+    pub fn effect_mut(&mut self, index: usize) -> Option<&mut PostEffect> {
+        self.effects.get_mut(index).map(|slot| &mut slot.effect)
+    }
+
+    pub fn find(&self, kind: PostEffectKind) -> Option<usize> {
+        self.effects
+            .iter()
+            .position(|slot| slot.effect.kind() == kind)
+    }
+
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(slot) = self.effects.get_mut(index) {
+            slot.enabled = enabled;
+        }
+    }
+
+    pub fn is_enabled(&self, index: usize) -> bool {
+        self.effects.get(index).is_some_and(|slot| slot.enabled)
+    }
+
+    pub fn toggle(&mut self, kind: PostEffectKind) {
+        for slot in self
+            .effects
+            .iter_mut()
+            .filter(|slot| slot.effect.kind() == kind)
+        {
+            slot.enabled = !slot.enabled;
+        }
+    }
+
+    pub fn active_effects(&self) -> Vec<PostEffect> {
+        self.effects
+            .iter()
+            .filter(|slot| slot.enabled && slot.effect.is_visible())
+            .map(|slot| slot.effect)
+            .collect()
+    }
+
+    pub fn trigger_flash(&mut self, color: Color, duration: Duration) {
+        let index = match self.find(PostEffectKind::Flash) {
+            Some(index) => index,
+            None => self.add_effect(PostEffect::Flash { color, amount: 0.0 }),
+        };
+        self.effects[index].effect = PostEffect::Flash { color, amount: 1.0 };
+        self.flash_duration = duration.as_secs_f32();
+    }
+
+    pub fn update(&mut self, deltatime: Duration) {
+        let dt = deltatime.as_secs_f32();
+        self.time += dt;
+        let decay = if self.flash_duration > 0.0 {
+            dt / self.flash_duration
+        } else {
+            1.0
+        };
+        for slot in self.effects.iter_mut() {
+            if let PostEffect::Flash { amount, .. } = &mut slot.effect {
+                *amount = (*amount - decay).max(0.0);
+            }
+        }
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn draw<F>(&mut self, ctx: &mut Context, draw_fn: F) -> GameResult
+    where
+        F: FnOnce(&mut Context) -> GameResult,
+    {
+        let effects = self.active_effects();
+        if effects.is_empty() {
+            return draw_fn(ctx);
+        }
+
+        let screen_coordinates = graphics::screen_coordinates(ctx);
+        let (drawable_width, drawable_height) = graphics::drawable_size(ctx);
+        let width = drawable_width.round().max(1.0) as u16;
+        let height = drawable_height.round().max(1.0) as u16;
+        self.prepare(ctx, &effects, width, height)?;
+
+        graphics::set_canvas(ctx, Some(&self.canvases[0]));
+        graphics::clear(ctx, Color::new(0.0, 0.0, 0.0, 0.0));
+        if let Err(err) = draw_fn(ctx) {
+            graphics::set_canvas(ctx, None);
+            return Err(err);
+        }
+
+        let resolution = [width as f32, height as f32];
+        let param = DrawParam::default()
+            .dest(screen_coordinates.point())
+            .scale(Vector2 {
+                x: screen_coordinates.w / width as f32,
+                y: screen_coordinates.h / height as f32,
+            });
+        for (index, effect) in effects.iter().enumerate() {
+            let source = &self.canvases[index % 2];
+            if index + 1 == effects.len() {
+                graphics::set_canvas(ctx, None);
+            } else {
+                graphics::set_canvas(ctx, Some(&self.canvases[(index + 1) % 2]));
+                graphics::clear(ctx, Color::new(0.0, 0.0, 0.0, 0.0));
+            }
+            let shader = &self.shaders[&effect.kind()];
+            let _lock = graphics::use_shader(ctx, shader);
+            let result = shader
+                .send(ctx, effect.uniforms(resolution, self.time))
+                .and_then(|_| graphics::draw(ctx, source, param));
+            if let Err(err) = result {
+                graphics::set_canvas(ctx, None);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn prepare(
+        &mut self,
+        ctx: &mut Context,
+        effects: &[PostEffect],
+        width: u16,
+        height: u16,
+    ) -> GameResult {
+        let needs_canvases = self.canvases.len() != 2
+            || self
+                .canvases
+                .iter()
+                .any(|canvas| canvas.image().width() != width || canvas.image().height() != height);
+        if needs_canvases {
+            self.canvases = vec![
+                Canvas::new(ctx, width, height, NumSamples::One)?,
+                Canvas::new(ctx, width, height, NumSamples::One)?,
+            ];
+        }
+        for effect in effects.iter() {
+            let kind = effect.kind();
+            if self.shaders.contains_key(&kind) {
+                continue;
+            }
+            let shader = Shader::from_u8(
+                ctx,
+                VERTEX_SHADER,
+                pixel_shader(kind),
+                effect.uniforms([width as f32, height as f32], self.time),
+                UNIFORM_BLOCK,
+                None,
+            )?;
+            self.shaders.insert(kind, shader);
+        }
+        Ok(())
+    }
+}
+
+fn pixel_shader(kind: PostEffectKind) -> &'static [u8] {
+    match kind {
+        PostEffectKind::Crt => CRT_SHADER,
+        PostEffectKind::Vignette => VIGNETTE_SHADER,
+        PostEffectKind::ColorGrade => COLOR_GRADE_SHADER,
+        PostEffectKind::Flash => FLASH_SHADER,
+        PostEffectKind::Pixelate => PIXELATE_SHADER,
+    }
+}
@@ -0,0 +1,265 @@
+use std::{cmp::Ordering, collections::HashMap, ops::Range, rc::Rc};
+
+use ggez::{
+    graphics::{spritebatch::SpriteBatch, BlendMode, DrawParam, Drawable, Image, Rect},
+    Context, GameResult,
+};
+
+use crate::{
+    rendering::{SpriteInstance, WorldDrawParams, WorldDrawable},
+    world::World,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortKey {
+    Z(i32),
+    Y(f32),
+}
+
+impl SortKey {
+    pub fn value(&self) -> f32 {
+        match self {
+            SortKey::Z(z) => *z as f32,
+            SortKey::Y(y) => -y,
+        }
+    }
+
+    pub fn rank(&self) -> u8 {
+        match self {
+            SortKey::Z(_) => 0,
+            SortKey::Y(_) => 1,
+        }
+    }
+
+    pub fn compare(&self, other: &SortKey) -> Ordering {
+        self.rank().cmp(&other.rank()).then(
+            self.value()
+                .partial_cmp(&other.value())
+                .unwrap_or(Ordering::Equal),
+        )
+    }
+}
+
+type DrawFn<'a> = Box<dyn FnOnce(&mut Context, &World) -> GameResult + 'a>;
+
+enum RenderItem<'a> {
+    Drawable(&'a dyn WorldDrawable, Rect, WorldDrawParams),
+    Sprite(SpriteInstance, Rect, WorldDrawParams),
+    Custom(DrawFn<'a>),
+}
+
+struct RenderCommand<'a> {
+    layer: i32,
+    sort_key: SortKey,
+    order: usize,
+    item: RenderItem<'a>,
+}
+
+impl RenderCommand<'_> {
+    fn texture(&self) -> Option<(*const Image, Option<BlendMode>)> {
+        match &self.item {
+            RenderItem::Sprite(instance, _, _) => {
+                Some((Rc::as_ptr(&instance.image), instance.blend_mode))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RenderStats {
+    pub commands: usize,
+    pub draw_calls: usize,
+}
+
+#[derive(Default)]
+pub struct SpriteBatches {
+    batches: HashMap<*const Image, (Rc<Image>, SpriteBatch, bool)>,
+}
+
+impl SpriteBatches {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.batches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    fn batch(&mut self, image: &Rc<Image>) -> &mut SpriteBatch {
+        let (_, batch, used) = self
+            .batches
+            .entry(Rc::as_ptr(image))
+            .or_insert_with(|| (Rc::clone(image), SpriteBatch::new((**image).clone()), false));
+        *used = true;
+        batch.clear();
+        batch
+    }
+
+    fn retain_used(&mut self) {
+        self.batches
+            .retain(|_, (_, _, used)| std::mem::replace(used, false));
+    }
+}
+
+#[derive(Default)]
+pub struct RenderQueue<'a> {
+    commands: Vec<RenderCommand<'a>>,
+    stats: RenderStats,
+}
+
+impl<'a> RenderQueue<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn submit(
+        &mut self,
+        layer: i32,
+        sort_key: SortKey,
+        drawable: &'a dyn WorldDrawable,
+        rect: Rect,
+        params: WorldDrawParams,
+    ) -> usize {
+        self.push(
+            layer,
+            sort_key,
+            RenderItem::Drawable(drawable, rect, params),
+        )
+    }
+
+    pub fn submit_sprite(
+        &mut self,
+        layer: i32,
+        sort_key: SortKey,
+        instance: SpriteInstance,
+        rect: Rect,
+        params: WorldDrawParams,
+    ) -> usize {
+        self.push(layer, sort_key, RenderItem::Sprite(instance, rect, params))
+    }
+
+    pub fn submit_fn<F>(&mut self, layer: i32, sort_key: SortKey, draw: F) -> usize
+    where
+        F: FnOnce(&mut Context, &World) -> GameResult + 'a,
+    {
+        self.push(layer, sort_key, RenderItem::Custom(Box::new(draw)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    pub fn sort(&mut self) {
+        self.commands.sort_by(|a, b| {
+            a.layer
+                .cmp(&b.layer)
+                .then(a.sort_key.compare(&b.sort_key))
+                .then(a.order.cmp(&b.order))
+        });
+    }
+
+    pub fn draw_order(&mut self) -> Vec<usize> {
+        self.sort();
+        self.commands.iter().map(|command| command.order).collect()
+    }
+
+    pub fn flush(&mut self, ctx: &mut Context, world: &World) -> GameResult {
+        self.flush_with(ctx, world, &mut SpriteBatches::new())
+    }
+
+    pub fn flush_with(
+        &mut self,
+        ctx: &mut Context,
+        world: &World,
+        batches: &mut SpriteBatches,
+    ) -> GameResult {
+        self.sort();
+        let textures: Vec<_> = self.commands.iter().map(RenderCommand::texture).collect();
+        let ranges = batch_ranges(&textures);
+        self.stats = RenderStats {
+            commands: self.commands.len(),
+            draw_calls: ranges.len(),
+        };
+
+        let mut commands = std::mem::take(&mut self.commands).into_iter();
+        let result = self.draw_batches(ctx, world, &mut commands, ranges, batches);
+        batches.retain_used();
+        result
+    }
+
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    fn draw_batches(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        commands: &mut std::vec::IntoIter<RenderCommand<'a>>,
+        ranges: Vec<Range<usize>>,
+        batches: &mut SpriteBatches,
+    ) -> GameResult {
+        for batch in ranges {
+            let mut batch_commands = commands.by_ref().take(batch.len());
+            let first = match batch_commands.next() {
+                Some(command) => command,
+                None => break,
+            };
+            match first.item {
+                RenderItem::Drawable(drawable, rect, params) => {
+                    drawable.draw_in_world_with_params(ctx, world, rect, params)?
+                }
+                RenderItem::Custom(draw) => draw(ctx, world)?,
+                RenderItem::Sprite(instance, rect, params) => {
+                    let sprite_batch = batches.batch(&instance.image);
+                    sprite_batch.set_blend_mode(instance.blend_mode);
+                    sprite_batch.add(instance.draw_param(world, rect, params));
+                    for command in batch_commands {
+                        if let RenderItem::Sprite(instance, rect, params) = command.item {
+                            sprite_batch.add(instance.draw_param(world, rect, params));
+                        }
+                    }
+                    sprite_batch.draw(ctx, DrawParam::default())?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, layer: i32, sort_key: SortKey, item: RenderItem<'a>) -> usize {
+        let order = self.commands.len();
+        self.commands.push(RenderCommand {
+            layer,
+            sort_key,
+            order,
+            item,
+        });
+        order
+    }
+}
+
+pub fn batch_ranges<K: PartialEq>(keys: &[Option<K>]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (index, key) in keys.iter().enumerate() {
+        let extends_last =
+            key.is_some() && ranges.last().is_some_and(|range| keys[range.start] == *key);
+        match ranges.last_mut() {
+            Some(range) if extends_last => range.end = index + 1,
+            _ => ranges.push(index..index + 1),
+        }
+    }
+    ranges
+}
@@ -1,89 +1,411 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, rc::Rc};
 
 use ggez::{
-    graphics::{Color, DrawParam, Drawable, Image, Rect},
+    graphics::{self, BlendMode, Color, DrawParam, Drawable, Image, Rect},
     mint::Point2,
     Context, GameResult,
 };
 
-use crate::{tilemap::Tilemap, world::World};
+use crate::{
+    render_queue::{RenderQueue, SortKey},
+    tilemap::Tilemap,
+    world::World,
+};
 
 pub const RED: Color = Color::new(1.0, 0.0, 0.0, 1.0);
-pub const GREEN: Color = Color::new(0.0, 1.0, 1.0, 1.0);
+pub const GREEN: Color = Color::new(0.0, 1.0, 0.0, 1.0);
 pub const BLUE: Color = Color::new(0.0, 0.0, 1.0, 1.0);
 
+const CENTER_PIVOT: Point2<f32> = Point2 { x: 0.5, y: 0.5 };
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldDrawParams {
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub rotation: f32,
+    pub pivot: Option<Point2<f32>>,
+    pub tint: Color,
+    pub alpha: f32,
+    pub z: i32,
+}
+
+impl Default for WorldDrawParams {
+    fn default() -> Self {
+        Self {
+            flip_x: false,
+            flip_y: false,
+            rotation: 0.0,
+            pivot: None,
+            tint: graphics::WHITE,
+            alpha: 1.0,
+            z: 0,
+        }
+    }
+}
+
+impl WorldDrawParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn flip_x(mut self, flip_x: bool) -> Self {
+        self.flip_x = flip_x;
+        self
+    }
+
+    pub fn flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn pivot(mut self, pivot: Point2<f32>) -> Self {
+        self.pivot = Some(pivot);
+        self
+    }
+
+    pub fn default_pivot(mut self, pivot: Point2<f32>) -> Self {
+        self.pivot = self.pivot.or(Some(pivot));
+        self
+    }
+
+    pub fn pivot_or_center(&self) -> Point2<f32> {
+        self.pivot.unwrap_or(CENTER_PIVOT)
+    }
+
+    pub fn tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn z(mut self, z: i32) -> Self {
+        self.z = z;
+        self
+    }
+
+    pub fn is_untransformed(&self) -> bool {
+        !self.flip_x && !self.flip_y && self.rotation == 0.0 && self.pivot.is_none()
+    }
+
+    pub fn color(&self) -> Color {
+        Color::new(
+            self.tint.r,
+            self.tint.g,
+            self.tint.b,
+            (self.tint.a * self.alpha).clamp(0.0, 1.0),
+        )
+    }
+
+    pub fn to_draw_param(&self, world: &World, rect: Rect, dimensions: Rect) -> DrawParam {
+        self.to_frame_draw_param(world, rect, dimensions, dimensions)
+    }
+
+    pub fn to_frame_draw_param(
+        &self,
+        world: &World,
+        rect: Rect,
+        frame: Rect,
+        source: Rect,
+    ) -> DrawParam {
+        let target_rect = world.world_to_screen_rect(rect);
+        let scale = Point2 {
+            x: target_rect.w / source.w,
+            y: target_rect.h / source.h,
+        };
+        let flip = |flipped: bool| if flipped { -1.0 } else { 1.0 };
+        let mirror = |pivot: f32, flipped: bool| if flipped { 1.0 - pivot } else { pivot };
+        let pivot = self.pivot_or_center();
+        let dest = world.snap_to_pixel_grid(
+            world.world_to_screen_pos_exact(Point2 {
+                x: rect.x,
+                y: rect.y,
+            }),
+            (frame.w * scale.x, frame.h * scale.y),
+            Point2 {
+                x: mirror(pivot.x, self.flip_x),
+                y: mirror(pivot.y, self.flip_y),
+            },
+        );
+        DrawParam::default()
+            .offset(pivot)
+            .dest(dest)
+            .rotation(world.rotation() + self.rotation)
+            .color(self.color())
+            .scale(Point2 {
+                x: flip(self.flip_x) * scale.x,
+                y: flip(self.flip_y) * scale.y,
+            })
+    }
+}
+
 pub trait WorldDrawable {
     fn draw_in_world(&self, ctx: &mut Context, world: &World, rect: Rect) -> GameResult;
+
+    fn draw_in_world_with_params(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        rect: Rect,
+        params: WorldDrawParams,
+    ) -> GameResult;
 }
 
 impl<T: Drawable> WorldDrawable for T {
     fn draw_in_world(&self, ctx: &mut Context, world: &World, rect: Rect) -> GameResult {
-        let target_rect = world.world_to_screen_rect(rect);
-        if let Some(initial_rect) = self.dimensions(ctx) {
-            return self.draw(
-                ctx,
-                DrawParam::default()
-                    .offset(Point2 { x: 0.5, y: 0.5 })
-                    .dest(target_rect.point())
-                    .scale(Point2 {
-                        x: target_rect.w / initial_rect.w,
-                        y: target_rect.h / initial_rect.h,
-                    }),
-            );
+        self.draw_in_world_with_params(ctx, world, rect, WorldDrawParams::default())
+    }
+
+    fn draw_in_world_with_params(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        rect: Rect,
+        params: WorldDrawParams,
+    ) -> GameResult {
+        if params.color().a <= 0.0 {
+            return Ok(());
+        }
+        if let Some(dimensions) = self.dimensions(ctx) {
+            return self.draw(ctx, params.to_draw_param(world, rect, dimensions));
         };
         Ok(())
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpriteFrame {
+    pub rect: Rect,
+    pub pivot: Point2<f32>,
+}
+
+impl SpriteFrame {
+    pub fn new(rect: Rect) -> Self {
+        Self {
+            rect,
+            pivot: Point2 { x: 0.5, y: 0.5 },
+        }
+    }
+
+    pub fn with_pivot(rect: Rect, pivot: Point2<f32>) -> Self {
+        Self { rect, pivot }
+    }
+
+    pub fn grid(width: f32, height: f32, rows: u32, cols: u32, total_sprites: u32) -> Vec<Self> {
+        let sprite_width = width / cols as f32;
+        let sprite_height = height / rows as f32;
+        (0..total_sprites)
+            .map(|index| {
+                let row_index = (index / cols) as f32;
+                let col_index = (index % cols) as f32;
+                Self::new(Rect::new(
+                    col_index * sprite_width,
+                    row_index * sprite_height,
+                    sprite_width,
+                    sprite_height,
+                ))
+            })
+            .collect()
+    }
+
+    pub fn source_rect(frames: &[SpriteFrame]) -> Rect {
+        let (width, height) = frames
+            .iter()
+            .fold((0.0f32, 0.0f32), |(width, height), frame| {
+                (width.max(frame.rect.w), height.max(frame.rect.h))
+            });
+        Rect::new(0.0, 0.0, width.max(1.0), height.max(1.0))
+    }
+
+    pub fn src_rect(&self, image_width: f32, image_height: f32) -> Rect {
+        Rect::new(
+            self.rect.x / image_width,
+            self.rect.y / image_height,
+            self.rect.w / image_width,
+            self.rect.h / image_height,
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct SpriteInstance {
+    pub image: Rc<Image>,
+    pub src: Rect,
+    pub pivot: Point2<f32>,
+    pub width: f32,
+    pub height: f32,
+    pub source: Rect,
+    pub blend_mode: Option<BlendMode>,
+}
+
+impl SpriteInstance {
+    pub fn draw_param(&self, world: &World, rect: Rect, params: WorldDrawParams) -> DrawParam {
+        params
+            .default_pivot(self.pivot)
+            .to_frame_draw_param(
+                world,
+                rect,
+                Rect::new(0.0, 0.0, self.width, self.height),
+                self.source,
+            )
+            .src(self.src)
+    }
+}
+
+impl WorldDrawable for SpriteInstance {
+    fn draw_in_world(&self, ctx: &mut Context, world: &World, rect: Rect) -> GameResult {
+        self.draw_in_world_with_params(ctx, world, rect, WorldDrawParams::default())
+    }
+
+    fn draw_in_world_with_params(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        rect: Rect,
+        params: WorldDrawParams,
+    ) -> GameResult {
+        if params.color().a <= 0.0 {
+            return Ok(());
+        }
+        self.image.draw(ctx, self.draw_param(world, rect, params))
+    }
+}
+
+#[derive(Clone)]
 pub struct SpriteSheet {
-    spritesheet: Image,
+    spritesheet: Rc<Image>,
+    frames: Rc<[SpriteFrame]>,
+    source: Rect,
     active_sprite_index: u32,
     active_sprite_rect: Rect,
     rows: u32,
     cols: u32,
-    total_sprites: u32,
-    sprite_width_proportion: f32,
-    sprite_height_proportion: f32,
+    blend_mode: Option<BlendMode>,
+    blended: Option<Rc<Image>>,
 }
 
 impl SpriteSheet {
     pub fn new(spritesheet: Image, rows: u32, cols: u32, total_sprites: u32) -> Self {
-        let sprite_width_proportion = 1.0 / cols as f32;
-        let sprite_height_proportion = 1.0 / rows as f32;
-        let active_sprite_rect =
-            Rect::new(0.0, 0.0, sprite_width_proportion, sprite_height_proportion);
-        SpriteSheet {
-            spritesheet,
+        Self::from_grid(Rc::new(spritesheet), rows, cols, total_sprites)
+    }
+
+    pub fn from_grid(spritesheet: Rc<Image>, rows: u32, cols: u32, total_sprites: u32) -> Self {
+        let frames = SpriteFrame::grid(
+            spritesheet.width() as f32,
+            spritesheet.height() as f32,
             rows,
             cols,
             total_sprites,
+        );
+        let mut sheet = Self::from_frames(spritesheet, frames.into());
+        sheet.rows = rows;
+        sheet.cols = cols;
+        sheet
+    }
+
+    pub fn from_frames(spritesheet: Rc<Image>, frames: Rc<[SpriteFrame]>) -> Self {
+        let cols = frames.len() as u32;
+        let mut sheet = SpriteSheet {
+            spritesheet,
+            source: SpriteFrame::source_rect(&frames),
+            frames,
+            rows: 1,
+            cols,
             active_sprite_index: 0,
-            active_sprite_rect,
-            sprite_width_proportion,
-            sprite_height_proportion,
-        }
+            active_sprite_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+            blend_mode: None,
+            blended: None,
+        };
+        sheet.set_active(0);
+        sheet
     }
 
     pub fn set_active(&mut self, sprite_index: u32) {
         self.active_sprite_index = sprite_index;
-        let row_index = (sprite_index / self.cols) as f32;
-        let col_index = (sprite_index % self.cols) as f32;
-        self.active_sprite_rect = Rect::new(
-            col_index * self.sprite_width_proportion,
-            row_index * self.sprite_height_proportion,
-            self.sprite_width_proportion,
-            self.sprite_height_proportion,
-        );
+        if let Some(frame) = self.frames.get(sprite_index as usize) {
+            self.active_sprite_rect = frame.src_rect(
+                self.spritesheet.width() as f32,
+                self.spritesheet.height() as f32,
+            );
+        }
     }
 
     pub fn set_next(&mut self) {
-        self.set_active((self.active_sprite_index + 1) % self.total_sprites);
+        if self.frames.is_empty() {
+            return;
+        }
+        self.set_active((self.active_sprite_index + 1) % self.total_sprites());
     }
 
     pub fn set_previous(&mut self) {
-        self.active_sprite_index =
-            (self.active_sprite_index + self.total_sprites - 1) % self.total_sprites;
-        self.set_active((self.active_sprite_index - 1) % self.total_sprites);
+        if self.frames.is_empty() {
+            return;
+        }
+        let total_sprites = self.total_sprites();
+        self.set_active((self.active_sprite_index + total_sprites - 1) % total_sprites);
+    }
+
+    pub fn active(&self) -> u32 {
+        self.active_sprite_index
+    }
+
+    pub fn pivot(&self) -> Point2<f32> {
+        self.active_frame()
+            .map_or(CENTER_PIVOT, |frame| frame.pivot)
+    }
+
+    pub fn active_frame(&self) -> Option<&SpriteFrame> {
+        self.frames.get(self.active_sprite_index as usize)
+    }
+
+    pub fn frames(&self) -> &[SpriteFrame] {
+        &self.frames
+    }
+
+    pub fn source(&self) -> Rect {
+        self.source
+    }
+
+    pub fn total_sprites(&self) -> u32 {
+        self.frames.len() as u32
+    }
+
+    pub fn image(&self) -> &Rc<Image> {
+        &self.spritesheet
+    }
+
+    pub fn set_image(&mut self, spritesheet: Rc<Image>) {
+        self.spritesheet = spritesheet;
+        self.update_blended();
+        self.set_active(self.active_sprite_index);
+    }
+
+    pub fn instance(&self) -> Option<SpriteInstance> {
+        self.frame_instance(self.active_sprite_index)
+    }
+
+    pub fn frame_instance(&self, sprite_index: u32) -> Option<SpriteInstance> {
+        let frame = self.frames.get(sprite_index as usize)?;
+        Some(SpriteInstance {
+            image: Rc::clone(self.blended.as_ref().unwrap_or(&self.spritesheet)),
+            src: frame.src_rect(
+                self.spritesheet.width() as f32,
+                self.spritesheet.height() as f32,
+            ),
+            pivot: frame.pivot,
+            width: frame.rect.w,
+            height: frame.rect.h,
+            source: self.source,
+            blend_mode: self.blend_mode,
+        })
     }
 
     pub fn rows(&self) -> u32 {
@@ -93,27 +415,33 @@ impl SpriteSheet {
     pub fn cols(&self) -> u32 {
         self.cols
     }
+
+    fn update_blended(&mut self) {
+        self.blended = self.blend_mode.map(|mode| {
+            let mut image = (*self.spritesheet).clone();
+            image.set_blend_mode(Some(mode));
+            Rc::new(image)
+        });
+    }
 }
 
 impl Drawable for SpriteSheet {
     fn draw(&self, ctx: &mut Context, param: DrawParam) -> GameResult {
-        self.spritesheet
-            .draw(ctx, param.src(self.active_sprite_rect))
+        let image = self.blended.as_ref().unwrap_or(&self.spritesheet);
+        image.draw(ctx, param.src(self.active_sprite_rect))
     }
 
     fn dimensions(&self, _ctx: &mut Context) -> Option<Rect> {
-        let mut rect = self.spritesheet.dimensions();
-        rect.w /= self.cols as f32;
-        rect.h /= self.rows as f32;
-        Some(rect)
+        self.active_frame().map(|_| self.source)
     }
 
-    fn set_blend_mode(&mut self, mode: Option<ggez::graphics::BlendMode>) {
-        self.spritesheet.set_blend_mode(mode);
+    fn set_blend_mode(&mut self, mode: Option<BlendMode>) {
+        self.blend_mode = mode;
+        self.update_blended();
     }
 
-    fn blend_mode(&self) -> Option<ggez::graphics::BlendMode> {
-        self.spritesheet.blend_mode()
+    fn blend_mode(&self) -> Option<BlendMode> {
+        self.blend_mode.or_else(|| self.spritesheet.blend_mode())
     }
 }
 
@@ -160,25 +488,70 @@ impl TilemapRenderer {
     pub fn tiles(&self) -> &Vec<Vec<u32>> {
         &self.tiles
     }
+
+    pub fn set_tiles(&mut self, template: &[&[u32]]) {
+        self.tiles = template.iter().map(|arr| Vec::from(*arr)).rev().collect();
+    }
+
+    pub fn set_image(&self, image: Rc<Image>) {
+        self.sprites.borrow_mut().set_image(image);
+    }
+
+    pub fn submit(&self, queue: &mut RenderQueue, layer: i32, params: WorldDrawParams) {
+        debug_assert!(
+            params.is_untransformed(),
+            "tilemaps only support tint, alpha and z"
+        );
+        let sprites = self.sprites.borrow();
+        for row in 0..self.tiles.len() {
+            for col in 0..self.tiles[row].len() {
+                if self.tiles[row][col] == 0 {
+                    continue;
+                }
+                if let Some(instance) = sprites.frame_instance(self.tiles[row][col] - 1) {
+                    let rect = Rect::new(
+                        (col as i32 - self.origin.x) as f32 * self.tile_width,
+                        (row as i32 - self.origin.y) as f32 * self.tile_height,
+                        self.tile_width,
+                        self.tile_height,
+                    );
+                    queue.submit_sprite(layer, SortKey::Z(params.z), instance, rect, params);
+                }
+            }
+        }
+    }
 }
 
 impl WorldDrawable for TilemapRenderer {
-    fn draw_in_world(&self, ctx: &mut Context, world: &World, _rect: Rect) -> GameResult {
+    fn draw_in_world(&self, ctx: &mut Context, world: &World, rect: Rect) -> GameResult {
+        self.draw_in_world_with_params(ctx, world, rect, WorldDrawParams::default())
+    }
+
+    fn draw_in_world_with_params(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        _rect: Rect,
+        params: WorldDrawParams,
+    ) -> GameResult {
+        debug_assert!(
+            params.is_untransformed(),
+            "tilemaps only support tint, alpha and z"
+        );
+        let sprites = self.sprites.borrow();
         for row in 0..self.tiles.len() {
             for col in 0..self.tiles[row].len() {
                 if self.tiles[row][col] == 0 {
                     continue;
                 }
-                let mut sprite_ref = self.sprites.borrow_mut();
-                sprite_ref.set_active(self.tiles[row][col] - 1);
-                let rect = Rect::new(
-                    (col as i32 - self.origin.x) as f32 * self.tile_width,
-                    (row as i32 - self.origin.y) as f32 * self.tile_height,
-                    self.tile_width,
-                    self.tile_height,
-                );
-                if let Err(err) = sprite_ref.draw_in_world(ctx, world, rect) {
-                    return Err(err);
+                if let Some(instance) = sprites.frame_instance(self.tiles[row][col] - 1) {
+                    let rect = Rect::new(
+                        (col as i32 - self.origin.x) as f32 * self.tile_width,
+                        (row as i32 - self.origin.y) as f32 * self.tile_height,
+                        self.tile_width,
+                        self.tile_height,
+                    );
+                    instance.draw_in_world_with_params(ctx, world, rect, params)?;
                 }
             }
         }
@@ -0,0 +1,115 @@
+use ggez::{
+    graphics::{self, Color, DrawMode, DrawParam, Mesh, Rect},
+    Context, GameResult,
+};
+
+use crate::world::World;
+
+#[derive(Clone, Copy)]
+pub enum ScalingPolicy {
+    Stretch,
+    Letterbox(f32),
+    Expand,
+}
+
+pub struct ScreenScaler {
+    policy: ScalingPolicy,
+    base_width: f32,
+    base_height: f32,
+    base_distance: f32,
+}
+
+impl ScreenScaler {
+    pub fn new(
+        policy: ScalingPolicy,
+        base_width: f32,
+        base_height: f32,
+        base_distance: f32,
+    ) -> Self {
+        Self {
+            policy,
+            base_width,
+            base_height,
+            base_distance,
+        }
+    }
+
+    pub fn set_policy(&mut self, policy: ScalingPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn viewport(&self, window_width: f32, window_height: f32) -> Rect {
+        match self.policy {
+            ScalingPolicy::Letterbox(aspect) => {
+                let (w, h) = if window_width / window_height > aspect {
+                    (window_height * aspect, window_height)
+                } else {
+                    (window_width, window_width / aspect)
+                };
+                Rect::new(0.5 * (window_width - w), 0.5 * (window_height - h), w, h)
+            }
+            _ => Rect::new(0.0, 0.0, window_width, window_height),
+        }
+    }
+
+    pub fn apply(&self, world: &mut World, window_width: f32, window_height: f32) -> Rect {
+        let viewport = self.viewport(window_width, window_height);
+        world.set_screen_dims(viewport.w, viewport.h);
+        match self.policy {
+            ScalingPolicy::Stretch => {
+                let base_world = World::new(self.base_width, self.base_height, self.base_distance);
+                world.set_world_dims(base_world.width(), base_world.height());
+            }
+            ScalingPolicy::Letterbox(_) => {
+                world.clear_world_dims();
+                world.set_distance(self.base_distance);
+            }
+            ScalingPolicy::Expand => {
+                world.clear_world_dims();
+                let base_side = self.base_width.min(self.base_height);
+                let side = viewport.w.min(viewport.h);
+                world.set_distance(self.base_distance * side / base_side);
+            }
+        }
+        viewport
+    }
+
+    pub fn resize(
+        &self,
+        ctx: &mut Context,
+        world: &mut World,
+        window_width: f32,
+        window_height: f32,
+    ) -> GameResult {
+        let viewport = self.apply(world, window_width, window_height);
+        graphics::set_screen_coordinates(
+            ctx,
+            Rect::new(-viewport.x, -viewport.y, window_width, window_height),
+        )
+    }
+
+    pub fn draw_letterbox(
+        &self,
+        ctx: &mut Context,
+        window_width: f32,
+        window_height: f32,
+        color: Color,
+    ) -> GameResult {
+        let viewport = self.viewport(window_width, window_height);
+        let bars = [
+            Rect::new(-viewport.x, -viewport.y, viewport.x, window_height),
+            Rect::new(viewport.w, -viewport.y, viewport.x, window_height),
+            Rect::new(-viewport.x, -viewport.y, window_width, viewport.y),
+            Rect::new(-viewport.x, viewport.h, window_width, viewport.y),
+        ];
+        for bar in bars.iter().filter(|bar| bar.w > 0.0 && bar.h > 0.0) {
+            let mesh = Mesh::new_rectangle(ctx, DrawMode::fill(), *bar, color)?;
+            graphics::draw(ctx, &mesh, DrawParam::default())?;
+        }
+        Ok(())
+    }
+
+    pub fn policy(&self) -> ScalingPolicy {
+        self.policy
+    }
+}
@@ -0,0 +1,932 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ggez::{
+    event::{Button as GamepadButton, KeyCode},
+    graphics::{
+        self, spritebatch::SpriteBatch, Color, DrawMode, DrawParam, Drawable, Font, Image, Mesh,
+        Rect, Scale, Text, TextFragment,
+    },
+    mint::{Point2, Vector2},
+    Context, GameResult,
+};
+
+use crate::rendering::SpriteSheet;
+
+const DEFAULT_TEXT_SIZE: f32 = 24.0;
+const DEFAULT_LINE_SPACING: f32 = 1.25;
+const SELECTION_MARKER: &str = "> ";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    pub fn pivot(&self) -> Point2<f32> {
+        let (x, y) = match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        };
+        Point2 { x, y }
+    }
+
+    pub fn place(&self, screen: Rect, offset: Vector2<f32>, size: (f32, f32)) -> Rect {
+        let pivot = self.pivot();
+        Rect::new(
+            screen.x + screen.w * pivot.x + offset.x - size.0 * pivot.x,
+            screen.y + screen.h * pivot.y + offset.y - size.1 * pivot.y,
+            size.0,
+            size.1,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Placement {
+    pub anchor: Anchor,
+    pub offset: Vector2<f32>,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self::new(Anchor::TopLeft, Vector2 { x: 0.0, y: 0.0 })
+    }
+}
+
+impl Placement {
+    pub fn new(anchor: Anchor, offset: Vector2<f32>) -> Self {
+        Self { anchor, offset }
+    }
+}
+
+pub trait UiElement {
+    fn placement(&self) -> Placement;
+
+    fn size(&self, ctx: &mut Context) -> (f32, f32);
+
+    fn draw_in_rect(&self, ctx: &mut Context, rect: Rect) -> GameResult;
+
+    fn layout(&self, ctx: &mut Context, screen: Rect) -> Rect {
+        let placement = self.placement();
+        let size = self.size(ctx);
+        placement.anchor.place(screen, placement.offset, size)
+    }
+
+    fn draw_ui(&self, ctx: &mut Context, screen: Rect) -> GameResult {
+        let rect = self.layout(ctx, screen);
+        self.draw_in_rect(ctx, rect)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UiInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Cancel,
+}
+
+impl UiInput {
+    pub fn from_keycode(keycode: KeyCode) -> Option<Self> {
+        match keycode {
+            KeyCode::Up | KeyCode::W => Some(UiInput::Up),
+            KeyCode::Down | KeyCode::S => Some(UiInput::Down),
+            KeyCode::Left | KeyCode::A => Some(UiInput::Left),
+            KeyCode::Right | KeyCode::D => Some(UiInput::Right),
+            KeyCode::Return | KeyCode::Space | KeyCode::Z => Some(UiInput::Confirm),
+            KeyCode::Escape | KeyCode::Back | KeyCode::X => Some(UiInput::Cancel),
+            _ => None,
+        }
+    }
+
+    pub fn from_button(button: GamepadButton) -> Option<Self> {
+        match button {
+            GamepadButton::DPadUp => Some(UiInput::Up),
+            GamepadButton::DPadDown => Some(UiInput::Down),
+            GamepadButton::DPadLeft => Some(UiInput::Left),
+            GamepadButton::DPadRight => Some(UiInput::Right),
+            GamepadButton::South | GamepadButton::Start => Some(UiInput::Confirm),
+            GamepadButton::East | GamepadButton::Select => Some(UiInput::Cancel),
+            _ => None,
+        }
+    }
+}
+
+pub struct UiLayer {
+    width: f32,
+    height: f32,
+    margin: f32,
+}
+
+impl UiLayer {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            margin: 0.0,
+        }
+    }
+
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    pub fn set_margin(&mut self, margin: f32) {
+        self.margin = margin;
+    }
+
+    pub fn screen_rect(&self) -> Rect {
+        Rect::new(0.0, 0.0, self.width, self.height)
+    }
+
+    pub fn layout_rect(&self) -> Rect {
+        let margin = self
+            .margin
+            .min(self.width / 2.0)
+            .min(self.height / 2.0)
+            .max(0.0);
+        Rect::new(
+            margin,
+            margin,
+            self.width - 2.0 * margin,
+            self.height - 2.0 * margin,
+        )
+    }
+
+    pub fn draw<F>(&self, ctx: &mut Context, draw_fn: F) -> GameResult
+    where
+        F: FnOnce(&mut Context, Rect) -> GameResult,
+    {
+        let screen_coordinates = graphics::screen_coordinates(ctx);
+        graphics::set_screen_coordinates(ctx, self.screen_rect())?;
+        let result = draw_fn(ctx, self.layout_rect());
+        graphics::set_screen_coordinates(ctx, screen_coordinates)?;
+        result
+    }
+}
+
+pub struct BitmapFont {
+    glyphs: SpriteSheet,
+    characters: Vec<char>,
+    glyph_width: f32,
+    glyph_height: f32,
+    batch: RefCell<SpriteBatch>,
+}
+
+impl BitmapFont {
+    pub fn new(image: Rc<Image>, rows: u32, cols: u32, characters: &str) -> Self {
+        let characters: Vec<char> = characters.chars().collect();
+        let glyph_width = image.width() as f32 / cols as f32;
+        let glyph_height = image.height() as f32 / rows as f32;
+        let total_glyphs = (characters.len() as u32).min(rows * cols);
+        let batch = RefCell::new(SpriteBatch::new((*image).clone()));
+        Self {
+            glyphs: SpriteSheet::from_grid(image, rows, cols, total_glyphs),
+            characters,
+            glyph_width,
+            glyph_height,
+            batch,
+        }
+    }
+
+    pub fn glyph_index(&self, character: char) -> Option<u32> {
+        self.characters
+            .iter()
+            .position(|c| *c == character)
+            .map(|index| index as u32)
+    }
+
+    pub fn measure(&self, text: &str, size: f32) -> (f32, f32) {
+        let scale = size / self.glyph_height;
+        let lines: Vec<&str> = text.split('\n').collect();
+        let longest = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        (
+            longest as f32 * self.glyph_width * scale,
+            lines.len() as f32 * self.glyph_height * scale,
+        )
+    }
+
+    pub fn draw_text(
+        &self,
+        ctx: &mut Context,
+        text: &str,
+        position: Point2<f32>,
+        size: f32,
+        color: Color,
+    ) -> GameResult {
+        let scale = size / self.glyph_height;
+        let mut batch = self.batch.borrow_mut();
+        batch.clear();
+        for (row, line) in text.split('\n').enumerate() {
+            for (col, character) in line.chars().enumerate() {
+                let instance = match self
+                    .glyph_index(character)
+                    .and_then(|index| self.glyphs.frame_instance(index))
+                {
+                    Some(instance) => instance,
+                    None => continue,
+                };
+                batch.add(
+                    DrawParam::default()
+                        .src(instance.src)
+                        .dest(Point2 {
+                            x: position.x + col as f32 * self.glyph_width * scale,
+                            y: position.y + row as f32 * self.glyph_height * scale,
+                        })
+                        .scale(Vector2 { x: scale, y: scale })
+                        .color(color),
+                );
+            }
+        }
+        graphics::draw(ctx, &*batch, DrawParam::default())
+    }
+}
+
+#[derive(Clone)]
+pub enum UiFont {
+    Ttf(Font),
+    Bitmap(Rc<BitmapFont>),
+}
+
+impl Default for UiFont {
+    fn default() -> Self {
+        UiFont::Ttf(Font::default())
+    }
+}
+
+impl UiFont {
+    pub fn measure(&self, ctx: &mut Context, text: &str, size: f32) -> (f32, f32) {
+        match self {
+            UiFont::Ttf(font) => {
+                let (width, height) = ttf_text(text, *font, size, graphics::WHITE).dimensions(ctx);
+                (width as f32, height as f32)
+            }
+            UiFont::Bitmap(font) => font.measure(text, size),
+        }
+    }
+
+    pub fn draw_text(
+        &self,
+        ctx: &mut Context,
+        text: &str,
+        position: Point2<f32>,
+        size: f32,
+        color: Color,
+    ) -> GameResult {
+        match self {
+            UiFont::Ttf(font) => graphics::draw(
+                ctx,
+                &ttf_text(text, *font, size, color),
+                DrawParam::default().dest(position),
+            ),
+            UiFont::Bitmap(font) => font.draw_text(ctx, text, position, size, color),
+        }
+    }
+}
+
+fn ttf_text(text: &str, font: Font, size: f32, color: Color) -> Text {
+    Text::new(
+        TextFragment::new(text)
+            .font(font)
+            .scale(Scale::uniform(size))
+            .color(color),
+    )
+}
+
+pub struct Label {
+    text: String,
+    font: UiFont,
+    text_size: f32,
+    color: Color,
+    placement: Placement,
+}
+
+impl Label {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            font: UiFont::default(),
+            text_size: DEFAULT_TEXT_SIZE,
+            color: graphics::WHITE,
+            placement: Placement::default(),
+        }
+    }
+
+    pub fn font(mut self, font: UiFont) -> Self {
+        self.font = font;
+        self
+    }
+
+    pub fn text_size(mut self, text_size: f32) -> Self {
+        self.text_size = text_size;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn placement(mut self, anchor: Anchor, offset: Vector2<f32>) -> Self {
+        self.placement = Placement::new(anchor, offset);
+        self
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            self.text = text.to_string();
+        }
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl UiElement for Label {
+    fn placement(&self) -> Placement {
+        self.placement
+    }
+
+    fn size(&self, ctx: &mut Context) -> (f32, f32) {
+        self.font.measure(ctx, &self.text, self.text_size)
+    }
+
+    fn draw_in_rect(&self, ctx: &mut Context, rect: Rect) -> GameResult {
+        self.font
+            .draw_text(ctx, &self.text, rect.point(), self.text_size, self.color)
+    }
+}
+
+pub struct UiImage {
+    image: Rc<Image>,
+    src: Rect,
+    width: f32,
+    height: f32,
+    color: Color,
+    placement: Placement,
+}
+
+impl UiImage {
+    pub fn new(image: Rc<Image>) -> Self {
+        let (width, height) = (image.width() as f32, image.height() as f32);
+        Self {
+            image,
+            src: Rect::new(0.0, 0.0, 1.0, 1.0),
+            width,
+            height,
+            color: graphics::WHITE,
+            placement: Placement::default(),
+        }
+    }
+
+    pub fn from_sprite(sprites: &SpriteSheet, sprite_index: u32) -> Option<Self> {
+        let instance = sprites.frame_instance(sprite_index)?;
+        Some(Self {
+            image: instance.image,
+            src: instance.src,
+            width: instance.width,
+            height: instance.height,
+            color: graphics::WHITE,
+            placement: Placement::default(),
+        })
+    }
+
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn placement(mut self, anchor: Anchor, offset: Vector2<f32>) -> Self {
+        self.placement = Placement::new(anchor, offset);
+        self
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+}
+
+impl UiElement for UiImage {
+    fn placement(&self) -> Placement {
+        self.placement
+    }
+
+    fn size(&self, _ctx: &mut Context) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    fn draw_in_rect(&self, ctx: &mut Context, rect: Rect) -> GameResult {
+        let texture_width = self.image.width() as f32 * self.src.w;
+        let texture_height = self.image.height() as f32 * self.src.h;
+        if texture_width <= 0.0 || texture_height <= 0.0 {
+            return Ok(());
+        }
+        self.image.draw(
+            ctx,
+            DrawParam::default()
+                .src(self.src)
+                .dest(rect.point())
+                .scale(Vector2 {
+                    x: rect.w / texture_width,
+                    y: rect.h / texture_height,
+                })
+                .color(self.color),
+        )
+    }
+}
+
+pub struct ProgressBar {
+    value: f32,
+    max: f32,
+    width: f32,
+    height: f32,
+    fill_color: Color,
+    background_color: Color,
+    border: Option<(Color, f32)>,
+    placement: Placement,
+}
+
+impl ProgressBar {
+    pub fn new(max: f32, width: f32, height: f32) -> Self {
+        Self {
+            value: max,
+            max,
+            width,
+            height,
+            fill_color: graphics::WHITE,
+            background_color: Color::new(0.0, 0.0, 0.0, 0.5),
+            border: None,
+            placement: Placement::default(),
+        }
+    }
+
+    pub fn colors(mut self, fill_color: Color, background_color: Color) -> Self {
+        self.fill_color = fill_color;
+        self.background_color = background_color;
+        self
+    }
+
+    pub fn border(mut self, color: Color, width: f32) -> Self {
+        self.border = Some((color, width));
+        self
+    }
+
+    pub fn placement(mut self, anchor: Anchor, offset: Vector2<f32>) -> Self {
+        self.placement = Placement::new(anchor, offset);
+        self
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value;
+    }
+
+    pub fn set_max(&mut self, max: f32) {
+        self.max = max;
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0.0 {
+            return 0.0;
+        }
+        (self.value / self.max).clamp(0.0, 1.0)
+    }
+
+    pub fn fill_rect(&self, rect: Rect) -> Rect {
+        Rect::new(rect.x, rect.y, rect.w * self.fraction(), rect.h)
+    }
+}
+
+impl UiElement for ProgressBar {
+    fn placement(&self) -> Placement {
+        self.placement
+    }
+
+    fn size(&self, _ctx: &mut Context) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    fn draw_in_rect(&self, ctx: &mut Context, rect: Rect) -> GameResult {
+        let background = Mesh::new_rectangle(ctx, DrawMode::fill(), rect, self.background_color)?;
+        graphics::draw(ctx, &background, DrawParam::default())?;
+        let fill_rect = self.fill_rect(rect);
+        if fill_rect.w > 0.0 {
+            let fill = Mesh::new_rectangle(ctx, DrawMode::fill(), fill_rect, self.fill_color)?;
+            graphics::draw(ctx, &fill, DrawParam::default())?;
+        }
+        if let Some((color, width)) = self.border {
+            let border = Mesh::new_rectangle(ctx, DrawMode::stroke(width), rect, color)?;
+            graphics::draw(ctx, &border, DrawParam::default())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Button {
+    pub label: String,
+    pub enabled: bool,
+}
+
+impl Button {
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuEvent {
+    Selected(usize),
+    Activated(usize),
+    Cancelled,
+}
+
+pub struct Menu {
+    buttons: Vec<Button>,
+    selected: usize,
+    wrap: bool,
+    font: UiFont,
+    text_size: f32,
+    color: Color,
+    highlight_color: Color,
+    disabled_color: Color,
+    placement: Placement,
+}
+
+impl Menu {
+    pub fn new(buttons: Vec<Button>) -> Self {
+        let mut menu = Self {
+            buttons,
+            selected: 0,
+            wrap: true,
+            font: UiFont::default(),
+            text_size: DEFAULT_TEXT_SIZE,
+            color: graphics::WHITE,
+            highlight_color: Color::new(1.0, 0.85, 0.3, 1.0),
+            disabled_color: Color::new(0.5, 0.5, 0.5, 1.0),
+            placement: Placement::new(Anchor::Center, Vector2 { x: 0.0, y: 0.0 }),
+        };
+        if !menu.is_enabled(0) {
+            menu.move_selection(1);
+        }
+        menu
+    }
+
+    pub fn with_labels(labels: &[&str]) -> Self {
+        Self::new(labels.iter().map(|label| Button::new(label)).collect())
+    }
+
+    pub fn font(mut self, font: UiFont) -> Self {
+        self.font = font;
+        self
+    }
+
+    pub fn text_size(mut self, text_size: f32) -> Self {
+        self.text_size = text_size;
+        self
+    }
+
+    pub fn colors(mut self, color: Color, highlight_color: Color, disabled_color: Color) -> Self {
+        self.color = color;
+        self.highlight_color = highlight_color;
+        self.disabled_color = disabled_color;
+        self
+    }
+
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn placement(mut self, anchor: Anchor, offset: Vector2<f32>) -> Self {
+        self.placement = Placement::new(anchor, offset);
+        self
+    }
+
+    pub fn buttons(&self) -> &[Button] {
+        &self.buttons
+    }
+
+    pub fn button_mut(&mut self, index: usize) -> Option<&mut Button> {
+        self.buttons.get_mut(index)
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if self.is_enabled(index) {
+            self.selected = index;
+        }
+    }
+
+    pub fn handle_input(&mut self, input: UiInput) -> Option<MenuEvent> {
+        match input {
+            UiInput::Up => self.move_selection(-1).map(MenuEvent::Selected),
+            UiInput::Down => self.move_selection(1).map(MenuEvent::Selected),
+            UiInput::Confirm if self.is_enabled(self.selected) => {
+                Some(MenuEvent::Activated(self.selected))
+            }
+            UiInput::Cancel => Some(MenuEvent::Cancelled),
+            _ => None,
+        }
+    }
+
+    fn is_enabled(&self, index: usize) -> bool {
+        self.buttons.get(index).is_some_and(|button| button.enabled)
+    }
+
+    fn move_selection(&mut self, step: i32) -> Option<usize> {
+        let count = self.buttons.len() as i32;
+        let mut index = self.selected as i32;
+        for _ in 0..count {
+            index += step;
+            if self.wrap {
+                index = index.rem_euclid(count);
+            } else if index < 0 || index >= count {
+                return None;
+            }
+            if self.is_enabled(index as usize) {
+                self.selected = index as usize;
+                return Some(self.selected);
+            }
+        }
+        None
+    }
+
+    fn rows(&self) -> ListRows<'_> {
+        ListRows {
+            font: &self.font,
+            text_size: self.text_size,
+            selected: self.selected,
+        }
+    }
+}
+
+impl UiElement for Menu {
+    fn placement(&self) -> Placement {
+        self.placement
+    }
+
+    fn size(&self, ctx: &mut Context) -> (f32, f32) {
+        self.rows()
+            .size(ctx, self.buttons.iter().map(|button| button.label.clone()))
+    }
+
+    fn draw_in_rect(&self, ctx: &mut Context, rect: Rect) -> GameResult {
+        for (index, button) in self.buttons.iter().enumerate() {
+            let color = if !button.enabled {
+                self.disabled_color
+            } else if index == self.selected {
+                self.highlight_color
+            } else {
+                self.color
+            };
+            self.rows().draw(ctx, rect, index, &button.label, color)?;
+        }
+        Ok(())
+    }
+}
+
+struct ListRows<'a> {
+    font: &'a UiFont,
+    text_size: f32,
+    selected: usize,
+}
+
+impl ListRows<'_> {
+    fn line_height(&self) -> f32 {
+        self.text_size * DEFAULT_LINE_SPACING
+    }
+
+    fn text(&self, index: usize, label: &str) -> String {
+        let marker = if index == self.selected {
+            SELECTION_MARKER.to_string()
+        } else {
+            " ".repeat(SELECTION_MARKER.len())
+        };
+        format!("{}{}", marker, label)
+    }
+
+    fn size<I>(&self, ctx: &mut Context, labels: I) -> (f32, f32)
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut width: f32 = 0.0;
+        let mut count = 0;
+        for (index, label) in labels.enumerate() {
+            let text = self.text(index, &label);
+            width = width.max(self.font.measure(ctx, &text, self.text_size).0);
+            count += 1;
+        }
+        (width, count as f32 * self.line_height())
+    }
+
+    fn draw(
+        &self,
+        ctx: &mut Context,
+        rect: Rect,
+        index: usize,
+        label: &str,
+        color: Color,
+    ) -> GameResult {
+        self.font.draw_text(
+            ctx,
+            &self.text(index, label),
+            Point2 {
+                x: rect.x,
+                y: rect.y + index as f32 * self.line_height(),
+            },
+            self.text_size,
+            color,
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tweak {
+    pub label: String,
+    pub value: f32,
+    pub step: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Tweak {
+    pub fn new(label: &str, value: f32, step: f32) -> Self {
+        Self {
+            label: label.to_string(),
+            value,
+            step,
+            min: f32::MIN,
+            max: f32::MAX,
+        }
+    }
+
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.min = min;
+        self.max = max;
+        self.value = self.value.clamp(min, max);
+        self
+    }
+
+    pub fn adjust(&mut self, steps: f32) -> bool {
+        let value = (self.value + steps * self.step).clamp(self.min, self.max);
+        let changed = (value - self.value).abs() > f32::EPSILON;
+        self.value = value;
+        changed
+    }
+}
+
+pub struct TweakPanel {
+    tweaks: Vec<Tweak>,
+    selected: usize,
+    font: UiFont,
+    text_size: f32,
+    color: Color,
+    highlight_color: Color,
+    background_color: Color,
+    placement: Placement,
+}
+
+impl TweakPanel {
+    pub fn new(tweaks: Vec<Tweak>) -> Self {
+        Self {
+            tweaks,
+            selected: 0,
+            font: UiFont::default(),
+            text_size: DEFAULT_TEXT_SIZE,
+            color: graphics::WHITE,
+            highlight_color: Color::new(1.0, 0.85, 0.3, 1.0),
+            background_color: Color::new(0.0, 0.0, 0.0, 0.6),
+            placement: Placement::new(Anchor::Right, Vector2 { x: 0.0, y: 0.0 }),
+        }
+    }
+
+    pub fn font(mut self, font: UiFont) -> Self {
+        self.font = font;
+        self
+    }
+
+    pub fn text_size(mut self, text_size: f32) -> Self {
+        self.text_size = text_size;
+        self
+    }
+
+    pub fn placement(mut self, anchor: Anchor, offset: Vector2<f32>) -> Self {
+        self.placement = Placement::new(anchor, offset);
+        self
+    }
+
+    pub fn tweaks(&self) -> &[Tweak] {
+        &self.tweaks
+    }
+
+    pub fn tweak(&self, label: &str) -> Option<&Tweak> {
+        self.tweaks.iter().find(|tweak| tweak.label == label)
+    }
+
+    pub fn set_value(&mut self, label: &str, value: f32) {
+        if let Some(tweak) = self.tweaks.iter_mut().find(|tweak| tweak.label == label) {
+            tweak.value = value.clamp(tweak.min, tweak.max);
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn handle_input(&mut self, input: UiInput) -> Option<usize> {
+        if self.tweaks.is_empty() {
+            return None;
+        }
+        let count = self.tweaks.len();
+        match input {
+            UiInput::Up => self.selected = (self.selected + count - 1) % count,
+            UiInput::Down => self.selected = (self.selected + 1) % count,
+            UiInput::Left if self.tweaks[self.selected].adjust(-1.0) => return Some(self.selected),
+            UiInput::Right if self.tweaks[self.selected].adjust(1.0) => return Some(self.selected),
+            _ => (),
+        }
+        None
+    }
+
+    fn rows(&self) -> ListRows<'_> {
+        ListRows {
+            font: &self.font,
+            text_size: self.text_size,
+            selected: self.selected,
+        }
+    }
+
+    fn label(&self, index: usize) -> String {
+        let tweak = &self.tweaks[index];
+        format!("{}: {:.2}", tweak.label, tweak.value)
+    }
+}
+
+impl UiElement for TweakPanel {
+    fn placement(&self) -> Placement {
+        self.placement
+    }
+
+    fn size(&self, ctx: &mut Context) -> (f32, f32) {
+        self.rows()
+            .size(ctx, (0..self.tweaks.len()).map(|index| self.label(index)))
+    }
+
+    fn draw_in_rect(&self, ctx: &mut Context, rect: Rect) -> GameResult {
+        let background = Mesh::new_rectangle(ctx, DrawMode::fill(), rect, self.background_color)?;
+        graphics::draw(ctx, &background, DrawParam::default())?;
+        for index in 0..self.tweaks.len() {
+            let color = if index == self.selected {
+                self.highlight_color
+            } else {
+                self.color
+            };
+            self.rows()
+                .draw(ctx, rect, index, &self.label(index), color)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use ggez::{
+    conf::NumSamples,
+    graphics::{self, Canvas, Color, DrawParam, Rect},
+    mint::Point2,
+    Context, GameResult,
+};
+
+use crate::{camera::Camera, world::World};
+
+pub struct Viewport {
+    screen_rect: Rect,
+    world: World,
+    camera: Box<dyn Camera>,
+    background: Color,
+}
+
+#[derive(Default)]
+pub struct ViewportCanvas {
+    canvas: Option<Canvas>,
+}
+
+impl ViewportCanvas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn fit(&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult<&Canvas> {
+        let width = width.ceil().max(1.0) as u16;
+        let height = height.ceil().max(1.0) as u16;
+        let needs_canvas = match &self.canvas {
+            Some(canvas) => canvas.image().width() < width || canvas.image().height() < height,
+            None => true,
+        };
+        if needs_canvas {
+            let (width, height) = match &self.canvas {
+                Some(canvas) => (
+                    width.max(canvas.image().width()),
+                    height.max(canvas.image().height()),
+                ),
+                None => (width, height),
+            };
+            self.canvas = Some(Canvas::new(ctx, width, height, NumSamples::One)?);
+        }
+        Ok(self.canvas.as_ref().unwrap())
+    }
+}
+
+impl Viewport {
+    pub fn new(screen_rect: Rect, distance: f32, camera: Box<dyn Camera>) -> Self {
+        let mut world = World::new(screen_rect.w, screen_rect.h, distance);
+        world.look_at(camera.position());
+        Self {
+            screen_rect,
+            world,
+            camera,
+            background: Color::new(0.0, 0.0, 0.0, 0.0),
+        }
+    }
+
+    pub fn update(&mut self, deltatime: Duration) {
+        self.camera.update(deltatime);
+        self.world.look_at(self.camera.position());
+    }
+
+    pub fn set_screen_rect(&mut self, screen_rect: Rect) {
+        self.screen_rect = screen_rect;
+        self.world.set_screen_dims(screen_rect.w, screen_rect.h);
+    }
+
+    pub fn set_background(&mut self, background: Color) {
+        self.background = background;
+    }
+
+    pub fn contains(&self, screen_point: Point2<f32>) -> bool {
+        self.screen_rect.contains(screen_point)
+    }
+
+    pub fn world_to_screen_pos(&self, position: Point2<f32>) -> Point2<f32> {
+        let local = self.world.world_to_screen_pos(position);
+        Point2 {
+            x: local.x + self.screen_rect.x,
+            y: local.y + self.screen_rect.y,
+        }
+    }
+
+    pub fn world_to_screen_rect(&self, rect: Rect) -> Rect {
+        let mut local = self.world.world_to_screen_rect(rect);
+        local.translate(self.screen_rect.point());
+        local
+    }
+
+    pub fn screen_to_world_pos(&self, position: Point2<f32>) -> Point2<f32> {
+        self.world.screen_to_world_pos(Point2 {
+            x: position.x - self.screen_rect.x,
+            y: position.y - self.screen_rect.y,
+        })
+    }
+
+    pub fn draw<F>(&self, ctx: &mut Context, canvas: &mut ViewportCanvas, draw_fn: F) -> GameResult
+    where
+        F: FnOnce(&mut Context, &World) -> GameResult,
+    {
+        let canvas = canvas.fit(ctx, self.screen_rect.w, self.screen_rect.h)?;
+        let canvas_width = f32::from(canvas.image().width());
+        let canvas_height = f32::from(canvas.image().height());
+
+        let screen_coordinates = graphics::screen_coordinates(ctx);
+        graphics::set_canvas(ctx, Some(canvas));
+        let result =
+            graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, canvas_width, canvas_height))
+                .and_then(|()| {
+                    graphics::clear(ctx, self.background);
+                    draw_fn(ctx, &self.world)
+                });
+        graphics::set_canvas(ctx, None);
+        graphics::set_screen_coordinates(ctx, screen_coordinates)?;
+        result?;
+
+        graphics::draw(
+            ctx,
+            canvas.image(),
+            DrawParam::default()
+                .src(canvas_region(
+                    self.screen_rect.w,
+                    self.screen_rect.h,
+                    canvas_width,
+                    canvas_height,
+                ))
+                .dest(Point2 {
+                    x: self.screen_rect.x,
+                    y: self.screen_rect.y + self.screen_rect.h,
+                })
+                .scale([1.0, -1.0]),
+        )
+    }
+
+    pub fn screen_rect(&self) -> Rect {
+        self.screen_rect
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn camera(&self) -> &dyn Camera {
+        self.camera.as_ref()
+    }
+
+    pub fn camera_mut(&mut self) -> &mut dyn Camera {
+        self.camera.as_mut()
+    }
+}
+
+pub fn split_horizontal(screen_width: f32, screen_height: f32, count: u32) -> Vec<Rect> {
+    let width = screen_width / count as f32;
+    (0..count)
+        .map(|index| Rect::new(index as f32 * width, 0.0, width, screen_height))
+        .collect()
+}
+
+pub fn split_vertical(screen_width: f32, screen_height: f32, count: u32) -> Vec<Rect> {
+    let height = screen_height / count as f32;
+    (0..count)
+        .map(|index| Rect::new(0.0, index as f32 * height, screen_width, height))
+        .collect()
+}
+
+pub fn canvas_region(width: f32, height: f32, canvas_width: f32, canvas_height: f32) -> Rect {
+    let w = (width / canvas_width).min(1.0);
+    let h = (height / canvas_height).min(1.0);
+    Rect::new(0.0, 1.0 - h, w, h)
+}
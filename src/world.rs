@@ -6,6 +6,9 @@ pub struct World {
     width: f32,
     height: f32,
     distance: f32,
+    world_aspect: Option<f32>,
+    rotation: f32,
+    pixel_snap: bool,
     camera_position: Point2<f32>,
 }
 
@@ -17,6 +20,9 @@ impl World {
             width: 0.0,
             height: 0.0,
             distance,
+            world_aspect: None,
+            rotation: 0.0,
+            pixel_snap: false,
             camera_position: Point2 { x: 0.0, y: 0.0 },
         };
         world.set_distance(distance);
@@ -25,15 +31,36 @@ impl World {
 
     pub fn set_distance(&mut self, distance: f32) {
         self.distance = distance;
-        if self.screen_width > self.screen_height {
+        let aspect = self
+            .world_aspect
+            .unwrap_or(self.screen_width / self.screen_height);
+        if aspect > 1.0 {
             self.height = 2.0 * distance;
-            self.width = 2.0 * distance * self.screen_width / self.screen_height;
+            self.width = 2.0 * distance * aspect;
         } else {
             self.width = 2.0 * distance;
-            self.height = 2.0 * distance * self.screen_height / self.screen_width;
+            self.height = 2.0 * distance / aspect;
         }
     }
 
+    pub fn set_world_dims(&mut self, width: f32, height: f32) {
+        self.world_aspect = Some(width / height);
+        self.set_distance(0.5 * width.min(height));
+    }
+
+    pub fn clear_world_dims(&mut self) {
+        self.world_aspect = None;
+        self.set_distance(self.distance);
+    }
+
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+    }
+
+    pub fn set_pixel_snap(&mut self, pixel_snap: bool) {
+        self.pixel_snap = pixel_snap;
+    }
+
     pub fn look_at(&mut self, position: Point2<f32>) {
         self.camera_position = position;
     }
@@ -45,19 +72,80 @@ impl World {
     }
 
     pub fn world_to_screen_pos(&self, position: Point2<f32>) -> Point2<f32> {
+        let screen_position = self.world_to_screen_pos_exact(position);
+        if self.pixel_snap {
+            return Point2 {
+                x: screen_position.x.round(),
+                y: screen_position.y.round(),
+            };
+        }
+        screen_position
+    }
+
+    pub fn world_to_screen_pos_exact(&self, position: Point2<f32>) -> Point2<f32> {
+        let (sin, cos) = self.rotation.sin_cos();
+        let dx = position.x - self.camera_position.x;
+        let dy = position.y - self.camera_position.y;
+        let local_x = dx * cos + dy * sin;
+        let local_y = dy * cos - dx * sin;
+        Point2 {
+            x: (0.5 + local_x / self.width) * self.screen_width,
+            y: (0.5 - local_y / self.height) * self.screen_height,
+        }
+    }
+
+    pub fn snap_to_pixel_grid(
+        &self,
+        position: Point2<f32>,
+        size: (f32, f32),
+        pivot: Point2<f32>,
+    ) -> Point2<f32> {
+        if !self.pixel_snap {
+            return position;
+        }
+        let left = position.x - pivot.x * size.0;
+        let top = position.y - pivot.y * size.1;
+        Point2 {
+            x: left.round() + pivot.x * size.0,
+            y: top.round() + pivot.y * size.1,
+        }
+    }
+
+    pub fn screen_to_world_pos(&self, position: Point2<f32>) -> Point2<f32> {
+        let (sin, cos) = self.rotation.sin_cos();
+        let local_x = (position.x / self.screen_width - 0.5) * self.width;
+        let local_y = (0.5 - position.y / self.screen_height) * self.height;
         Point2 {
-            x: (0.5 + (position.x - self.camera_position.x) / self.width) * self.screen_width,
-            y: (0.5 - (position.y - self.camera_position.y) / self.height) * self.screen_height,
+            x: self.camera_position.x + local_x * cos - local_y * sin,
+            y: self.camera_position.y + local_x * sin + local_y * cos,
         }
     }
 
     pub fn world_to_screen_rect(&self, rect: Rect) -> Rect {
         let new_pos = self.world_to_screen_pos(rect.point());
-        Rect::new(
-            new_pos.x,
-            new_pos.y,
-            self.screen_width * rect.w / self.width,
-            self.screen_height * rect.h / self.height,
+        let (scale_x, scale_y) = self.rect_scale();
+        let mut w = rect.w * scale_x;
+        let mut h = rect.h * scale_y;
+        if self.pixel_snap {
+            w = w.round();
+            h = h.round();
+        }
+        Rect::new(new_pos.x, new_pos.y, w, h)
+    }
+
+    pub fn screen_to_world_rect(&self, rect: Rect) -> Rect {
+        let new_pos = self.screen_to_world_pos(rect.point());
+        let (scale_x, scale_y) = self.rect_scale();
+        Rect::new(new_pos.x, new_pos.y, rect.w / scale_x, rect.h / scale_y)
+    }
+
+    fn rect_scale(&self) -> (f32, f32) {
+        let (sin, cos) = self.rotation.sin_cos();
+        let scale_x = self.screen_width / self.width;
+        let scale_y = self.screen_height / self.height;
+        (
+            (cos * scale_x).hypot(sin * scale_y),
+            (sin * scale_x).hypot(cos * scale_y),
         )
     }
 
@@ -84,4 +172,12 @@ impl World {
     pub fn distance(&self) -> f32 {
         self.distance
     }
+
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    pub fn pixel_snap(&self) -> bool {
+        self.pixel_snap
+    }
 }
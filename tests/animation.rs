@@ -1,5 +1,12 @@
+use std::time::Duration;
+
 use ::ezplatform::animation::*;
 
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
 #[test]
 fn test_state_machine_update_once() {
     let mut state_machine: StateMachine<bool> = StateMachine::new(3);
@@ -48,3 +55,434 @@ fn test_state_machine_update_with_limit() {
     state_machine.update_with_limit(true, 5);
     assert_eq!(1, state_machine.state());
 }
+
+#[test]
+fn test_playback_loop_carries_remainder() {
+    let mut playback = Playback::new(3, 10.0);
+
+    playback.update(Duration::from_secs_f32(0.15));
+    assert_eq!(1, playback.frame());
+    assert_eq_float!(0.05, playback.frame_time(), DELTA);
+
+    playback.update(Duration::from_secs_f32(0.06));
+    assert_eq!(2, playback.frame());
+
+    playback.update(Duration::from_secs_f32(0.25));
+    assert_eq!(1, playback.frame());
+    assert!(!playback.is_finished());
+}
+
+#[test]
+fn test_playback_once() {
+    let mut playback = Playback::new(3, 10.0);
+    playback.set_mode(PlaybackMode::Once);
+
+    playback.update(Duration::from_secs_f32(0.25));
+    assert_eq!(2, playback.frame());
+    assert!(!playback.is_finished());
+
+    playback.update(Duration::from_secs_f32(0.1));
+    assert_eq!(2, playback.frame());
+    assert!(playback.is_finished());
+
+    playback.update(Duration::from_secs_f32(1.0));
+    assert_eq!(2, playback.frame());
+
+    playback.reset();
+    assert_eq!(0, playback.frame());
+    assert!(!playback.is_finished());
+}
+
+#[test]
+fn test_playback_ping_pong() {
+    let mut playback = Playback::new(3, 10.0);
+    playback.set_mode(PlaybackMode::PingPong);
+
+    let mut frames = Vec::new();
+    for _ in 0..8 {
+        playback.update(Duration::from_secs_f32(0.1001));
+        frames.push(playback.frame());
+    }
+    assert_eq!(vec![1, 2, 1, 0, 1, 2, 1, 0], frames);
+}
+
+#[test]
+fn test_playback_reverse() {
+    let mut playback = Playback::new(3, 10.0);
+    playback.set_mode(PlaybackMode::Reverse);
+    assert_eq!(2, playback.frame());
+
+    let mut frames = Vec::new();
+    for _ in 0..4 {
+        playback.update(Duration::from_secs_f32(0.1001));
+        frames.push(playback.frame());
+    }
+    assert_eq!(vec![1, 0, 2, 1], frames);
+}
+
+#[test]
+fn test_playback_speed_and_frame_durations() {
+    let mut playback = Playback::with_frame_durations(vec![0.1, 0.3, 0.2]);
+    assert_eq!(3, playback.frame_count());
+    assert_eq_float!(5.0, playback.fps(), DELTA);
+
+    playback.set_speed(2.0);
+    playback.update(Duration::from_secs_f32(0.1));
+    assert_eq!(1, playback.frame());
+    assert_eq_float!(0.1, playback.frame_time(), DELTA);
+
+    playback.update(Duration::from_secs_f32(0.1));
+    assert_eq!(2, playback.frame());
+    assert_eq_float!(0.0, playback.frame_time(), DELTA);
+    assert_eq_float!(0.2, playback.current_frame_duration(), DELTA);
+}
+
+#[test]
+fn test_playback_reports_every_entered_frame() {
+    let mut playback = Playback::new(4, 10.0);
+
+    assert!(playback.update(Duration::from_secs_f32(0.05)).is_empty());
+    assert_eq!(
+        vec![1, 2, 3, 0, 1],
+        playback.update(Duration::from_secs_f32(0.5))
+    );
+
+    playback.set_mode(PlaybackMode::Once);
+    assert_eq!(vec![1, 2, 3], playback.update(Duration::from_secs_f32(1.0)));
+    assert!(playback.update(Duration::from_secs_f32(1.0)).is_empty());
+}
+
+#[test]
+fn test_frame_events_collect() {
+    let mut events = FrameEvents::new();
+    assert!(events.is_empty());
+
+    events.add_frame_event(1, "footstep");
+    events.add_frame_event(3, "footstep");
+    events.add_marker("contact", 2);
+    events.add_marker_event("contact", "hitbox_on");
+    events.add_marker_event("missing", "never");
+    assert!(!events.is_empty());
+    assert_eq!(Some(2), events.marker("contact"));
+
+    let collected = events.collect(&[1, 2, 3, 0]);
+    let names: Vec<_> = collected
+        .iter()
+        .map(|event| (event.name.as_str(), event.frame))
+        .collect();
+    assert_eq!(
+        vec![("footstep", 1), ("hitbox_on", 2), ("footstep", 3)],
+        names
+    );
+
+    events.add_marker("contact", 0);
+    let collected = events.collect(&[2, 0]);
+    assert_eq!(
+        vec![AnimationEvent {
+            name: "hitbox_on".to_string(),
+            frame: 0
+        }],
+        collected
+    );
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum TestState {
+    Idle,
+    Walk,
+    Jump,
+    Grounded,
+    Airborne,
+}
+
+#[test]
+fn test_state_machine_named_states_and_priorities() {
+    let mut state_machine: StateMachine<f32, TestState> =
+        StateMachine::with_initial_state(TestState::Idle);
+
+    state_machine.add_prioritized_rule(TestState::Idle, TestState::Walk, 0, |speed| *speed > 1.0);
+    state_machine.add_prioritized_rule(TestState::Idle, TestState::Jump, 5, |speed| *speed > 2.0);
+
+    state_machine.step(&1.5);
+    assert_eq!(TestState::Walk, state_machine.state());
+
+    state_machine.set_state(TestState::Idle);
+    state_machine.step(&3.0);
+    assert_eq!(TestState::Jump, state_machine.state());
+}
+
+#[test]
+fn test_state_machine_any_state_rules() {
+    let mut state_machine: StateMachine<bool, TestState> =
+        StateMachine::with_initial_state(TestState::Idle);
+
+    state_machine.add_rule(TestState::Idle, TestState::Walk, |monitor| monitor);
+    let any_rule = state_machine.add_any_rule(TestState::Jump, 1, |monitor| *monitor);
+
+    assert_eq!(Some(any_rule), state_machine.step(&true));
+    assert_eq!(TestState::Jump, state_machine.state());
+    assert_eq!(None, state_machine.step(&true));
+    assert_eq!(TestState::Jump, state_machine.state());
+}
+
+#[test]
+fn test_conditions() {
+    let mut parameters = Parameters::new();
+    parameters.set_float("speed", 2.0);
+    parameters.set_bool("grounded", true);
+
+    assert!(Condition::greater("speed", 1.0).is_met(&parameters));
+    assert!(!Condition::less("speed", 1.0).is_met(&parameters));
+    assert!(Condition::is_true("grounded").is_met(&parameters));
+    assert!(Condition::is_false("missing").is_met(&parameters));
+    assert!(!Condition::trigger("attack").is_met(&parameters));
+
+    parameters.set_trigger("attack");
+    assert!(Condition::trigger("attack").is_met(&parameters));
+}
+
+#[test]
+fn test_state_machine_transitions_consume_triggers() {
+    let mut state_machine: StateMachine<Parameters, TestState> =
+        StateMachine::with_initial_state(TestState::Idle);
+    state_machine.add_transition(
+        TestState::Idle,
+        TestState::Jump,
+        vec![Condition::is_true("grounded"), Condition::trigger("jump")],
+    );
+    state_machine.add_transition(
+        TestState::Jump,
+        TestState::Idle,
+        vec![Condition::is_true("grounded")],
+    );
+
+    let mut parameters = Parameters::new();
+    parameters.set_trigger("jump");
+    assert!(!state_machine.update_parameters(&mut parameters));
+    assert!(parameters.is_triggered("jump"));
+
+    parameters.set_bool("grounded", true);
+    assert!(state_machine.update_parameters(&mut parameters));
+    assert_eq!(TestState::Jump, state_machine.state());
+    assert!(!parameters.is_triggered("jump"));
+
+    assert!(state_machine.update_parameters(&mut parameters));
+    assert!(!state_machine.update_parameters(&mut parameters));
+    assert_eq!(TestState::Idle, state_machine.state());
+}
+
+#[test]
+fn test_state_machine_hooks() {
+    use std::{cell::RefCell, rc::Rc};
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut state_machine: StateMachine<bool, TestState> =
+        StateMachine::with_initial_state(TestState::Idle);
+    state_machine.add_rule(TestState::Idle, TestState::Walk, |monitor| monitor);
+
+    let exit_log = Rc::clone(&log);
+    state_machine.on_exit(TestState::Idle, move |transition| {
+        exit_log
+            .borrow_mut()
+            .push(format!("exit {:?}", transition.from))
+    });
+    let enter_log = Rc::clone(&log);
+    state_machine.on_enter(TestState::Walk, move |transition| {
+        enter_log
+            .borrow_mut()
+            .push(format!("enter {:?}", transition.to))
+    });
+    let update_log = Rc::clone(&log);
+    state_machine.on_update(TestState::Walk, move |_, time_in_state| {
+        update_log
+            .borrow_mut()
+            .push(format!("update {:.1}", time_in_state))
+    });
+
+    state_machine.tick(Duration::from_secs_f32(0.5));
+    state_machine.update_once(true);
+    state_machine.tick(Duration::from_secs_f32(0.5));
+
+    assert_eq!(
+        vec!["exit Idle", "enter Walk", "update 0.5"],
+        log.borrow().clone()
+    );
+}
+
+#[test]
+fn test_state_machine_min_time_and_cooldown() {
+    let mut state_machine: StateMachine<bool, TestState> =
+        StateMachine::with_initial_state(TestState::Idle);
+    state_machine.add_rule(TestState::Idle, TestState::Walk, |monitor| monitor);
+    let back = state_machine.add_rule(TestState::Walk, TestState::Idle, |monitor| monitor);
+    state_machine.set_min_time_in_state(TestState::Walk, Duration::from_secs_f32(1.0));
+    state_machine.set_rule_cooldown(back, Duration::from_secs_f32(3.0));
+
+    assert!(state_machine.update_once(true));
+    assert!(!state_machine.update_once(true));
+    state_machine.tick(Duration::from_secs_f32(1.0));
+    assert!(state_machine.update_once(true));
+    assert_eq!(TestState::Idle, state_machine.state());
+
+    assert!(state_machine.update_once(true));
+    state_machine.tick(Duration::from_secs_f32(1.5));
+    assert!(!state_machine.update_once(true));
+    state_machine.tick(Duration::from_secs_f32(1.5));
+    assert!(state_machine.update_once(true));
+    assert_eq!(TestState::Idle, state_machine.state());
+}
+
+#[test]
+fn test_state_machine_history() {
+    let mut state_machine: StateMachine<bool, TestState> =
+        StateMachine::with_initial_state(TestState::Idle);
+    let forward = state_machine.add_rule(TestState::Idle, TestState::Walk, |monitor| monitor);
+    state_machine.add_rule(TestState::Walk, TestState::Idle, |monitor| monitor);
+    state_machine.set_history_limit(2);
+
+    state_machine.update_once(true);
+    state_machine.tick(Duration::from_secs_f32(0.25));
+    state_machine.update_once(true);
+    state_machine.update_once(true);
+    state_machine.transition_to(TestState::Jump);
+
+    let history = state_machine.history();
+    assert_eq!(2, history.len());
+    assert_eq!(TestState::Idle, history[0].from);
+    assert_eq!(Some(forward), history[0].rule);
+    assert_eq_float!(0.25, history[0].time, DELTA);
+    assert_eq!(
+        Some(&Transition {
+            from: TestState::Walk,
+            to: TestState::Jump,
+            rule: None,
+            time: 0.25,
+        }),
+        state_machine.last_transition()
+    );
+}
+
+fn create_hierarchical_machine() -> HierarchicalStateMachine<Parameters, TestState> {
+    let mut grounded = StateMachine::with_initial_state(TestState::Idle);
+    grounded.add_transition(
+        TestState::Idle,
+        TestState::Walk,
+        vec![Condition::greater("speed", 1.0)],
+    );
+    let mut machine = HierarchicalStateMachine::with_initial_state(TestState::Grounded);
+    machine.machine_mut().add_transition(
+        TestState::Grounded,
+        TestState::Airborne,
+        vec![Condition::is_false("grounded")],
+    );
+    machine.machine_mut().add_transition(
+        TestState::Airborne,
+        TestState::Grounded,
+        vec![Condition::is_true("grounded")],
+    );
+    machine.add_sub_machine(TestState::Grounded, HierarchicalStateMachine::new(grounded));
+    machine
+}
+
+#[test]
+fn test_hierarchical_state_machine_sub_states() {
+    let mut machine = create_hierarchical_machine();
+    let mut parameters = Parameters::new();
+    parameters.set_bool("grounded", true);
+
+    assert_eq!(TestState::Idle, machine.state());
+    parameters.set_float("speed", 2.0);
+    assert!(machine.update_parameters(&mut parameters));
+    assert_eq!(
+        vec![TestState::Grounded, TestState::Walk],
+        machine.active_path()
+    );
+    assert!(machine.is_in(TestState::Grounded));
+    assert!(!machine.is_in(TestState::Idle));
+}
+
+#[test]
+fn test_hierarchical_state_machine_parent_transitions() {
+    let mut machine = create_hierarchical_machine();
+    let mut parameters = Parameters::new();
+    parameters.set_bool("grounded", true);
+    parameters.set_float("speed", 2.0);
+    machine.update_parameters(&mut parameters);
+    assert_eq!(TestState::Walk, machine.state());
+
+    parameters.set_bool("grounded", false);
+    assert!(machine.update_parameters(&mut parameters));
+    assert_eq!(TestState::Airborne, machine.state());
+    assert_eq!(vec![TestState::Airborne], machine.active_path());
+
+    parameters.set_bool("grounded", true);
+    parameters.set_float("speed", 0.0);
+    assert!(machine.update_parameters(&mut parameters));
+    assert_eq!(TestState::Idle, machine.state());
+
+    parameters.set_bool("grounded", false);
+    machine.update_parameters(&mut parameters);
+    parameters.set_bool("grounded", true);
+    parameters.set_float("speed", 2.0);
+    assert!(machine.update_parameters(&mut parameters));
+    assert_eq!(TestState::Walk, machine.state());
+}
+
+#[test]
+fn test_playback_normalized_time() {
+    let mut playback = Playback::with_frame_durations(vec![0.1, 0.3, 0.1]);
+
+    playback.update(Duration::from_secs_f32(0.25));
+    assert_eq!(1, playback.frame());
+    assert_eq_float!(0.5, playback.normalized_time(), DELTA);
+
+    playback.set_normalized_time(0.9);
+    assert_eq!(2, playback.frame());
+    assert_eq_float!(0.05, playback.frame_time(), DELTA);
+
+    playback.set_normalized_time(0.0);
+    assert_eq!(0, playback.frame());
+    assert_eq_float!(0.0, playback.normalized_time(), DELTA);
+}
+
+#[test]
+fn test_cross_fade_weight() {
+    let settings = BlendSettings::cross_fade(Duration::from_secs_f32(0.2)).with_sync_time();
+    assert!(settings.sync_time);
+    assert!(!settings.hold_until_finished);
+
+    let mut fade = CrossFade::new(3, settings.fade_duration);
+    assert_eq!(3, fade.from());
+    assert_eq_float!(0.0, fade.weight(), DELTA);
+
+    fade.update(Duration::from_secs_f32(0.05));
+    assert_eq_float!(0.25, fade.weight(), DELTA);
+    assert!(!fade.is_finished());
+
+    fade.update(Duration::from_secs_f32(0.5));
+    assert_eq_float!(1.0, fade.weight(), DELTA);
+    assert!(fade.is_finished());
+}
+
+#[test]
+fn test_cross_fade_alphas_keep_full_opacity() {
+    let (outgoing, incoming) = cross_fade_alphas(0.8, 0.5);
+    assert_eq_float!(0.8, outgoing, DELTA);
+    assert_eq_float!(0.4, incoming, DELTA);
+
+    for step in 0..=10 {
+        let weight = step as f32 / 10.0;
+        let (outgoing, incoming) = cross_fade_alphas(1.0, weight);
+        assert_eq_float!(1.0, incoming + outgoing * (1.0 - incoming), DELTA);
+        assert_eq_float!(weight, incoming, DELTA);
+    }
+
+    let (_, incoming) = cross_fade_alphas(1.0, 2.0);
+    assert_eq_float!(1.0, incoming, DELTA);
+}
+
+#[test]
+fn test_sprite_animator_needs_a_state() {
+    assert!(SpriteAnimator::from_animations(Vec::new()).is_none());
+    assert!(SpriteAnimator::<u8>::from_states(Vec::new()).is_none());
+}
@@ -0,0 +1,99 @@
+use ezplatform::aseprite::*;
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+const SHEET: &str = r#"{
+    "frames": {
+        "player 0.aseprite": { "frame": { "x": 0, "y": 0, "w": 32, "h": 32 }, "duration": 100 },
+        "player 1.aseprite": { "frame": { "x": 32, "y": 0, "w": 32, "h": 32 }, "duration": 50 },
+        "player 2.aseprite": { "frame": { "x": 64, "y": 0, "w": 32, "h": 32 }, "duration": 50 },
+        "player 3.aseprite": { "frame": { "x": 96, "y": 0, "w": 32, "h": 32 }, "duration": 200 },
+        "player 4.aseprite": { "frame": { "x": 128, "y": 0, "w": 32, "h": 32 }, "duration": 250 }
+    },
+    "meta": {
+        "app": "https://www.aseprite.org/",
+        "image": "player.png",
+        "frameTags": [
+            { "name": "idle", "from": 0, "to": 0, "direction": "forward" },
+            { "name": "walk", "from": 1, "to": 3, "direction": "forward" },
+            { "name": "land", "from": 1, "to": 3, "direction": "reverse" },
+            { "name": "swing", "from": 1, "to": 4, "direction": "pingpong" },
+            { "name": "sway", "from": 2, "to": 4, "direction": "pingpong_reverse" }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_aseprite_parse() {
+    let sheet = AsepriteSheet::from_json(SHEET).unwrap();
+
+    assert_eq!(5, sheet.frames.len());
+    assert_eq!(5, sheet.tags.len());
+    assert_eq_float!(0.1, sheet.frames[0].duration.unwrap(), DELTA);
+    assert_eq_float!(0.25, sheet.frames[4].duration.unwrap(), DELTA);
+
+    let walk = sheet.tag("walk").unwrap();
+    assert_eq!(1, walk.from);
+    assert_eq!(3, walk.to);
+    assert_eq!(TagDirection::Forward, walk.direction);
+    assert_eq!(TagDirection::Reverse, sheet.tag("land").unwrap().direction);
+    assert_eq!(
+        TagDirection::PingPong,
+        sheet.tag("swing").unwrap().direction
+    );
+    assert!(sheet.tag("missing").is_none());
+}
+
+#[test]
+fn test_aseprite_frame_sequence() {
+    let sheet = AsepriteSheet::from_json(SHEET).unwrap();
+
+    let sequence = |name| sheet.frame_sequence(sheet.tag(name).unwrap());
+    assert_eq!(vec![0], sequence("idle"));
+    assert_eq!(vec![1, 2, 3], sequence("walk"));
+    assert_eq!(vec![3, 2, 1], sequence("land"));
+    assert_eq!(vec![1, 2, 3, 4, 3, 2], sequence("swing"));
+    assert_eq!(vec![4, 3, 2, 3], sequence("sway"));
+}
+
+#[test]
+fn test_aseprite_frame_durations() {
+    let sheet = AsepriteSheet::from_json(SHEET).unwrap();
+
+    let durations = sheet.frame_durations(sheet.tag("land").unwrap());
+    assert_eq!(3, durations.len());
+    assert_eq_float!(0.2, durations[0], DELTA);
+    assert_eq_float!(0.05, durations[1], DELTA);
+    assert_eq_float!(0.05, durations[2], DELTA);
+}
+
+#[test]
+fn test_aseprite_default_tag() {
+    let sheet = AsepriteSheet::from_json(
+        r#"{ "frames": [
+            { "filename": "a", "frame": { "x": 0, "y": 0, "w": 8, "h": 8 }, "duration": 100 },
+            { "filename": "b", "frame": { "x": 8, "y": 0, "w": 8, "h": 8 }, "duration": 100 }
+        ], "meta": {} }"#,
+    )
+    .unwrap();
+
+    let tag = sheet.tag(DEFAULT_TAG).unwrap();
+    assert_eq!(vec![0, 1], sheet.frame_sequence(tag));
+}
+
+#[test]
+fn test_aseprite_invalid_tags() {
+    assert!(AsepriteSheet::from_json(
+        r#"{ "frames": [ { "filename": "a", "frame": { "x": 0, "y": 0, "w": 8, "h": 8 } } ],
+             "meta": { "frameTags": [ { "name": "bad", "from": 0, "to": 3 } ] } }"#
+    )
+    .is_err());
+    assert!(AsepriteSheet::from_json(
+        r#"{ "frames": [ { "filename": "a", "frame": { "x": 0, "y": 0, "w": 8, "h": 8 } } ],
+             "meta": { "frameTags": [ { "name": "bad", "from": 0, "to": 0, "direction": "sideways" } ] } }"#
+    )
+    .is_err());
+}
@@ -0,0 +1,172 @@
+use std::{
+    env, fs,
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
+
+use ::ezplatform::assets::*;
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_level_parse() {
+    let level = Level::parse("# cave\n1 2 3\n\n0 0 4 # floor\n").unwrap();
+    assert_eq!(3, level.width());
+    assert_eq!(2, level.height());
+    assert_eq!(&vec![vec![1, 2, 3], vec![0, 0, 4]], level.tiles());
+    assert_eq!(vec![&[1, 2, 3][..], &[0, 0, 4][..]], level.template());
+
+    assert!(Level::parse("1 2\n3\n").is_err());
+    assert!(Level::parse("1 x\n").is_err());
+    assert!(Level::parse("# empty\n").is_err());
+}
+
+#[test]
+fn test_file_watcher_detects_changes() {
+    let dir = env::temp_dir().join(format!("ezplatform_assets_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("ground.png");
+    fs::write(&path, "v1").unwrap();
+
+    let handle = AssetHandle::new(AssetKind::Image, 0);
+    let missing = AssetHandle::new(AssetKind::Sound, 0);
+    let mut watcher = FileWatcher::new();
+    watcher.set_poll_interval(Duration::from_secs(1));
+    watcher.watch(handle, &path);
+    watcher.watch(missing, dir.join("missing.wav"));
+    assert!(watcher.is_watching(handle));
+    assert_eq!(Some(path.as_path()), watcher.path(handle));
+    assert!(watcher.changed().is_empty());
+
+    let file = fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(10))
+        .unwrap();
+    assert!(watcher.poll(Duration::from_secs_f32(0.5)).is_empty());
+    assert_eq!(vec![handle], watcher.poll(Duration::from_secs_f32(0.5)));
+    assert!(watcher.changed().is_empty());
+
+    watcher.unwatch(handle);
+    assert!(!watcher.is_watching(handle));
+    assert_eq!(
+        Some(dir.join("ground.png")),
+        resolve_on_disk(&[dir.join("nowhere"), dir.clone()], "/ground.png")
+    );
+    assert_eq!(
+        None,
+        resolve_on_disk(&[dir.join("nowhere"), dir.clone()], "/missing.wav")
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+fn level(text: &str) -> Rc<Level> {
+    Rc::new(Level::parse(text).unwrap())
+}
+
+#[test]
+fn test_storage_deduplicates_and_counts_references() {
+    let mut storage = AssetStorage::new();
+    let cave = storage.insert("/cave.txt", level("1 2"), false);
+    let shared = storage.insert("/cave.txt", level("3 4"), false);
+    assert_eq!(cave, shared);
+    assert_eq!(2, storage.ref_count(&cave));
+    assert_eq!(&vec![vec![1, 2]], storage.get(&cave).unwrap().tiles());
+
+    let bonus = storage.insert("/bonus.txt", level("5"), true);
+    assert_ne!(cave, bonus);
+    assert_eq!(2, storage.len());
+    assert_eq!(Some(bonus.clone()), storage.find("/bonus.txt"));
+    assert_eq!(Some("/bonus.txt"), storage.path(&bonus));
+    assert!(storage.is_fallback(&bonus));
+    assert!(storage.modify(&bonus, |level| Level::parse(&format!(
+        "{} 7",
+        level.tiles()[0][0]
+    ))
+    .unwrap()));
+    assert!(storage.is_fallback(&bonus));
+    assert_eq!(&vec![vec![5, 7]], storage.get(&bonus).unwrap().tiles());
+    assert!(storage.replace(&bonus, level("6")));
+    assert!(!storage.is_fallback(&bonus));
+
+    let copy = cave.clone();
+    assert_eq!(3, storage.ref_count(&cave));
+    drop(copy);
+    drop(shared);
+    assert_eq!(1, storage.ref_count(&cave));
+    assert!(storage.collect().is_empty());
+    let index = cave.index();
+    drop(cave);
+    assert_eq!(vec![index], storage.collect());
+    assert_eq!(None, storage.find("/cave.txt"));
+    assert_eq!(1, storage.len());
+}
+
+#[test]
+fn test_typed_handles() {
+    let mut storage = AssetStorage::<Level>::new();
+    storage.insert("/skipped.txt", level("0"), false);
+    let handle = storage.insert("/cave.txt", level("1"), false);
+    assert_eq!(1, handle.index());
+    assert_eq!(AssetHandle::new(AssetKind::Level, 1), handle.untyped());
+    assert!(handle == AssetHandle::new(AssetKind::Level, 1));
+    assert!(handle != AssetHandle::new(AssetKind::Image, 1));
+    assert_eq!(
+        AssetHandle::new(AssetKind::Level, 1),
+        AssetHandle::from(&handle)
+    );
+}
+
+#[test]
+fn test_manifest() {
+    let manifest = AssetManifest::from_json(
+        r#"{ "images": ["/ground.png", "/jump.png"], "sounds": ["/jump.wav"] }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        AssetManifest::new()
+            .image("/ground.png")
+            .image("/jump.png")
+            .sound("/jump.wav"),
+        manifest
+    );
+    assert_eq!(3, manifest.len());
+    assert_eq!(
+        (AssetKind::Sound, "/jump.wav".to_string()),
+        manifest.entries()[2]
+    );
+
+    assert!(AssetManifest::from_json(r#"{ "fonts": [] }"#).is_err());
+    assert!(AssetManifest::from_json(r#"{ "images": "/ground.png" }"#).is_err());
+    assert!(AssetManifest::from_json(r#"{ "levels": [1] }"#).is_err());
+    assert!(AssetManifest::from_json("[]").is_err());
+}
+
+#[test]
+fn test_load_progress() {
+    let mut progress = LoadProgress {
+        path: "/ground.png".to_string(),
+        loaded: 1,
+        failed: 0,
+        total: 4,
+    };
+    assert_eq_float!(0.25, progress.fraction(), DELTA);
+    assert!(!progress.is_done());
+    progress.loaded = 4;
+    assert!(progress.is_done());
+    progress.total = 0;
+    assert_eq_float!(1.0, progress.fraction(), DELTA);
+}
+
+#[test]
+fn test_placeholder_pixels() {
+    let pixels = placeholder_pixels(4, 2);
+    assert_eq!(4 * 4 * 4, pixels.len());
+    let pixel = |x: usize, y: usize| &pixels[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+    assert_eq!(pixel(0, 0), pixel(1, 1));
+    assert_eq!(pixel(2, 0), pixel(0, 2));
+    assert_ne!(pixel(0, 0), pixel(2, 0));
+    assert_eq!(&[255, 0, 255, 255], pixel(0, 0));
+}
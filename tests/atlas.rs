@@ -0,0 +1,79 @@
+use ezplatform::atlas::*;
+use ggez::{graphics::Rect, mint::Point2};
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_parse_atlas_hash() {
+    let json = r#"{
+        "frames": {
+            "walk_0.png": { "frame": { "x": 0, "y": 0, "w": 32, "h": 32 } },
+            "walk_1.png": { "frame": { "x": 32, "y": 0, "w": 32, "h": 32 } },
+            "jump.png": { "frame": { "x": 0, "y": 32, "w": 16, "h": 24 } }
+        },
+        "meta": { "image": "player.png", "size": { "w": 64, "h": 64 } }
+    }"#;
+
+    let frames = parse_atlas_json(json).unwrap();
+    let names: Vec<_> = frames.iter().map(|frame| frame.name.as_str()).collect();
+    assert_eq!(vec!["walk_0.png", "walk_1.png", "jump.png"], names);
+    assert_eq_rect!(
+        Rect::new(32.0, 0.0, 32.0, 32.0),
+        frames[1].frame.rect,
+        DELTA
+    );
+    assert_eq_rect!(
+        Rect::new(0.0, 32.0, 16.0, 24.0),
+        frames[2].frame.rect,
+        DELTA
+    );
+    assert_eq_point!(Point2 { x: 0.5, y: 0.5 }, frames[2].frame.pivot, DELTA);
+}
+
+#[test]
+fn test_parse_atlas_array() {
+    let json = r#"{
+        "frames": [
+            { "filename": "b", "frame": { "x": 10, "y": 0, "w": 10, "h": 20 }, "pivot": { "x": 0.5, "y": 1.0 } },
+            { "filename": "a", "frame": { "x": 0, "y": 0, "w": 10, "h": 20 } }
+        ]
+    }"#;
+
+    let frames = parse_atlas_json(json).unwrap();
+    assert_eq!(2, frames.len());
+    assert_eq!("b", frames[0].name);
+    assert_eq!("a", frames[1].name);
+    assert_eq_point!(Point2 { x: 0.5, y: 1.0 }, frames[0].frame.pivot, DELTA);
+}
+
+#[test]
+fn test_parse_atlas_trimmed_pivot() {
+    let json = r#"{
+        "frames": {
+            "trimmed": {
+                "frame": { "x": 0, "y": 0, "w": 20, "h": 10 },
+                "trimmed": true,
+                "spriteSourceSize": { "x": 10, "y": 20, "w": 20, "h": 10 },
+                "sourceSize": { "w": 40, "h": 40 },
+                "pivot": { "x": 0.5, "y": 0.75 }
+            }
+        }
+    }"#;
+
+    let frames = parse_atlas_json(json).unwrap();
+    assert_eq_point!(Point2 { x: 0.5, y: 1.0 }, frames[0].frame.pivot, DELTA);
+}
+
+#[test]
+fn test_parse_atlas_errors() {
+    assert!(parse_atlas_json("not json").is_err());
+    assert!(parse_atlas_json(r#"{ "meta": {} }"#).is_err());
+    assert!(parse_atlas_json(r#"{ "frames": { "a": { "frame": { "x": 0 } } } }"#).is_err());
+    assert!(parse_atlas_json(
+        r#"{ "frames": { "a": { "frame": { "x": 0, "y": 0, "w": 1, "h": 1 }, "rotated": true } } }"#
+    )
+    .is_err());
+}
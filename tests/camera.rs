@@ -1,7 +1,10 @@
 use std::time::Duration;
 
-use ::ezplatform::camera::*;
-use ggez::mint::Point2;
+use ::ezplatform::{camera::*, collision::DynamicCollider, world::World};
+use ggez::{
+    graphics::Rect,
+    mint::{Point2, Vector2},
+};
 
 #[macro_use]
 mod float_asserts;
@@ -76,3 +79,235 @@ fn test_smooth_camera_follow() {
     camera.update(Duration::from_secs_f32(0.5));
     assert_eq_point!(Point2 { x: 0.0, y: 1.25 }, camera.position(), DELTA);
 }
+
+#[test]
+fn test_smooth_camera_dead_zone() {
+    let mut camera = SmoothCamera::new(Point2 { x: 0.0, y: 0.0 }, 0.5);
+    camera.set_dead_zone(Vector2 { x: 1.0, y: 1.0 });
+    assert_eq_rect!(
+        Rect::new(0.0, 0.0, 2.0, 2.0),
+        camera.dead_zone_rect(),
+        DELTA
+    );
+
+    camera.set_destination(Point2 { x: 0.5, y: -1.0 });
+    camera.update(Duration::from_secs_f32(1.0));
+    assert_eq_point!(Point2 { x: 0.0, y: 0.0 }, camera.position(), DELTA);
+
+    camera.set_destination(Point2 { x: 3.0, y: -3.0 });
+    camera.update(Duration::from_secs_f32(1.0));
+    assert_eq_point!(Point2 { x: 1.0, y: -1.0 }, camera.position(), DELTA);
+    assert_eq_point!(Point2 { x: 3.0, y: -3.0 }, camera.destination(), DELTA);
+}
+
+#[test]
+fn test_screen_shake_trauma() {
+    let mut shake = ScreenShake::new(Vector2 { x: 1.0, y: 1.0 }, 10.0, 0.5);
+    assert_eq_float!(0.0, shake.trauma(), DELTA);
+    assert_eq_point!(Vector2 { x: 0.0, y: 0.0 }, shake.offset(), DELTA);
+
+    shake.add_trauma(0.75);
+    shake.add_trauma(0.75);
+    assert_eq_float!(1.0, shake.trauma(), DELTA);
+
+    shake.update(Duration::from_secs_f32(1.0));
+    assert_eq_float!(0.5, shake.trauma(), DELTA);
+
+    shake.update(Duration::from_secs_f32(2.0));
+    assert_eq_float!(0.0, shake.trauma(), DELTA);
+    assert_eq_point!(Vector2 { x: 0.0, y: 0.0 }, shake.offset(), DELTA);
+}
+
+#[test]
+fn test_screen_shake_offset_bounds() {
+    let mut shake = ScreenShake::new(Vector2 { x: 2.0, y: 0.5 }, 7.0, 0.0);
+    shake.add_trauma(1.0);
+    let mut moved = false;
+    for _ in 0..100 {
+        shake.update(Duration::from_secs_f32(0.013));
+        let offset = shake.offset();
+        assert!(offset.x.abs() <= 2.0);
+        assert!(offset.y.abs() <= 0.5);
+        if offset.x.abs() > DELTA {
+            moved = true;
+        }
+    }
+    assert!(moved);
+}
+
+#[test]
+fn test_zoom_tween() {
+    let mut zoom = ZoomTween::new(8.0, 4.0, Duration::from_secs_f32(1.0));
+    assert_eq_float!(8.0, zoom.distance(), DELTA);
+
+    zoom.update(Duration::from_secs_f32(0.5));
+    assert_eq_float!(6.0, zoom.distance(), DELTA);
+    assert!(!zoom.is_finished());
+
+    zoom.update(Duration::from_secs_f32(1.0));
+    assert_eq_float!(4.0, zoom.distance(), DELTA);
+    assert!(zoom.is_finished());
+}
+
+#[test]
+fn test_timed_zoom_tween() {
+    let mut zoom = ZoomTween::timed(
+        8.0,
+        4.0,
+        Duration::from_secs_f32(1.0),
+        Duration::from_secs_f32(2.0),
+    );
+
+    zoom.update(Duration::from_secs_f32(1.0));
+    assert_eq_float!(4.0, zoom.distance(), DELTA);
+    zoom.update(Duration::from_secs_f32(1.5));
+    assert_eq_float!(4.0, zoom.distance(), DELTA);
+    zoom.update(Duration::from_secs_f32(1.0));
+    assert_eq_float!(6.0, zoom.distance(), DELTA);
+    assert!(!zoom.is_finished());
+    zoom.update(Duration::from_secs_f32(1.0));
+    assert_eq_float!(8.0, zoom.distance(), DELTA);
+    assert!(zoom.is_finished());
+}
+
+#[test]
+fn test_camera_pan() {
+    let origin = Point2 { x: 0.0, y: 0.0 };
+    let mut pan = CameraPan::new(
+        Point2 { x: 10.0, y: 4.0 },
+        Duration::from_secs_f32(1.0),
+        Duration::from_secs_f32(1.0),
+    );
+
+    assert_eq_point!(origin, pan.position(origin), DELTA);
+    pan.update(Duration::from_secs_f32(0.5));
+    assert_eq_point!(Point2 { x: 5.0, y: 2.0 }, pan.position(origin), DELTA);
+    pan.update(Duration::from_secs_f32(1.0));
+    assert_eq_point!(Point2 { x: 10.0, y: 4.0 }, pan.position(origin), DELTA);
+    pan.update(Duration::from_secs_f32(1.0));
+    assert_eq_point!(Point2 { x: 5.0, y: 2.0 }, pan.position(origin), DELTA);
+
+    let moved_origin = Point2 { x: 2.0, y: 0.0 };
+    pan.update(Duration::from_secs_f32(1.0));
+    assert_eq_point!(moved_origin, pan.position(moved_origin), DELTA);
+    assert!(pan.is_finished());
+}
+
+#[test]
+fn test_camera_cross_fade() {
+    let from = SharpCamera::new(Point2 { x: 0.0, y: 0.0 });
+    let to = SharpCamera::new(Point2 { x: 4.0, y: 8.0 });
+    let mut fade = CameraCrossFade::new(Box::new(from), Box::new(to), Duration::from_secs_f32(2.0));
+
+    assert_eq_point!(Point2 { x: 0.0, y: 0.0 }, fade.position(), DELTA);
+
+    fade.set_destination(Point2 { x: 2.0, y: 2.0 });
+    fade.update(Duration::from_secs_f32(1.0));
+    assert_eq_point!(Point2 { x: 2.0, y: 2.0 }, fade.position(), DELTA);
+    assert!(!fade.is_finished());
+
+    fade.update(Duration::from_secs_f32(1.0));
+    assert!(fade.is_finished());
+    let target = fade.into_target();
+    assert_eq_point!(Point2 { x: 2.0, y: 2.0 }, target.position(), DELTA);
+}
+
+#[test]
+fn test_camera_effects_apply() {
+    let mut world = World::new(800.0, 400.0, 8.0);
+    let mut effects = CameraEffects::new(ScreenShake::new(Vector2 { x: 1.0, y: 1.0 }, 10.0, 1.0));
+    let focus = Point2 { x: 3.0, y: 1.0 };
+
+    effects.apply(&mut world, focus);
+    assert_eq_point!(focus, world.camera_position(), DELTA);
+    assert_eq_float!(8.0, world.distance(), DELTA);
+
+    effects.zoom(ZoomTween::new(
+        world.distance(),
+        4.0,
+        Duration::from_secs_f32(1.0),
+    ));
+    effects.pan(CameraPan::new(
+        Point2 { x: 13.0, y: 1.0 },
+        Duration::from_secs_f32(1.0),
+        Duration::from_secs_f32(0.0),
+    ));
+    effects.update(Duration::from_secs_f32(0.5));
+    effects.apply(&mut world, focus);
+    assert_eq_point!(Point2 { x: 8.0, y: 1.0 }, world.camera_position(), DELTA);
+    assert_eq_float!(6.0, world.distance(), DELTA);
+
+    effects.update(Duration::from_secs_f32(1.5));
+    effects.apply(&mut world, focus);
+    assert_eq_float!(4.0, world.distance(), DELTA);
+    assert_eq_point!(focus, world.camera_position(), DELTA);
+
+    effects.update(Duration::from_secs_f32(0.1));
+    assert!(!effects.is_zooming());
+    assert!(!effects.is_panning());
+}
+
+#[test]
+fn test_group_framing_bounds() {
+    assert!(GroupFraming::bounds(&[]).is_none());
+
+    let first = DynamicCollider::from_rect(Rect::new(-4.0, 0.0, 2.0, 2.0), 1.0);
+    let second = DynamicCollider::from_rect(Rect::new(6.0, 3.0, 2.0, 4.0), 1.0);
+
+    let bounds = GroupFraming::bounds(&[&first]).unwrap();
+    assert_eq_rect!(Rect::new(-4.0, 0.0, 2.0, 2.0), bounds, DELTA);
+
+    let bounds = GroupFraming::bounds(&[&first, &second]).unwrap();
+    assert_eq_rect!(Rect::new(1.0, 2.0, 12.0, 6.0), bounds, DELTA);
+}
+
+#[test]
+fn test_group_framing_distance() {
+    let framing = GroupFraming::new(1.0, 4.0, 20.0);
+    let world = World::new(800.0, 400.0, 8.0);
+
+    assert_eq_float!(
+        4.0,
+        framing.distance(Rect::new(0.0, 0.0, 1.0, 1.0), &world),
+        DELTA
+    );
+    assert_eq_float!(
+        6.0,
+        framing.distance(Rect::new(0.0, 0.0, 2.0, 10.0), &world),
+        DELTA
+    );
+    assert_eq_float!(
+        8.0,
+        framing.distance(Rect::new(0.0, 0.0, 30.0, 2.0), &world),
+        DELTA
+    );
+    assert_eq_float!(
+        20.0,
+        framing.distance(Rect::new(0.0, 0.0, 100.0, 2.0), &world),
+        DELTA
+    );
+
+    let world = World::new(400.0, 800.0, 8.0);
+    assert_eq_float!(
+        8.0,
+        framing.distance(Rect::new(0.0, 0.0, 2.0, 30.0), &world),
+        DELTA
+    );
+}
+
+#[test]
+fn test_group_framing_frame() {
+    let framing = GroupFraming::new(1.0, 4.0, 20.0);
+    let mut world = World::new(800.0, 400.0, 8.0);
+    let mut camera = SharpCamera::new(Point2 { x: 0.0, y: 0.0 });
+
+    let first = DynamicCollider::from_rect(Rect::new(-4.0, 0.0, 2.0, 2.0), 1.0);
+    let second = DynamicCollider::from_rect(Rect::new(6.0, 3.0, 2.0, 4.0), 1.0);
+    framing.frame(&[&first, &second], &mut camera, &mut world);
+    camera.update(Duration::default());
+
+    assert_eq_point!(Point2 { x: 1.0, y: 2.0 }, camera.position(), DELTA);
+    assert_eq_float!(4.0, world.distance(), DELTA);
+    assert!(world.width() >= 14.0);
+    assert!(world.height() >= 8.0);
+}
@@ -1,5 +1,8 @@
 use ezplatform::{collision::*, physics::PhysicsObject};
-use ggez::{graphics::Rect, mint::Point2};
+use ggez::{
+    graphics::Rect,
+    mint::{Point2, Vector2},
+};
 
 #[macro_use]
 mod float_asserts;
@@ -176,3 +179,36 @@ fn test_dynamic_collider_resolve_collision_with_contact() {
     collider.resolve_collision(&obstacle);
     assert_eq_rect!(expected, collider.rect(), DELTA);
 }
+
+#[test]
+fn test_dynamic_collider_contacts() {
+    let mut collider = DynamicCollider::from_rect(Rect::new(1.0, 1.0, 2.0, 3.0), 1.0);
+    assert_eq!(
+        None,
+        collider.resolve_collision(&Rect::new(-1.0, 1.0, 1.0, 1.0))
+    );
+
+    let contact = collider
+        .resolve_collision(&Rect::new(0.0, 1.0, 1.0, 1.0))
+        .unwrap();
+    assert_eq_point!(Vector2 { x: 1.0, y: 0.0 }, contact.normal, DELTA);
+    assert_eq_point!(Point2 { x: 0.5, y: 1.0 }, contact.point, DELTA);
+
+    let contacts = collider.resolve_collisions(&[
+        Rect::new(1.5, -0.5, 1.0, 1.0),
+        Rect::new(10.0, 10.0, 1.0, 1.0),
+    ]);
+    assert_eq!(1, contacts.len());
+    assert_eq_point!(Vector2 { x: 0.0, y: 1.0 }, contacts[0].normal, DELTA);
+    assert_eq_point!(Point2 { x: 1.5, y: 0.0 }, contacts[0].point, DELTA);
+}
+
+#[test]
+fn test_tilemap_collider_bounds() {
+    let mut collider =
+        TilemapCollider::from_template(&[&[false, false, true], &[true, false, false]]);
+    assert_eq_rect!(Rect::new(1.0, 0.5, 3.0, 2.0), collider.bounds(), DELTA);
+
+    collider.set_origin(Point2 { x: 1, y: 0 });
+    assert_eq_rect!(Rect::new(0.0, 0.5, 3.0, 2.0), collider.bounds(), DELTA);
+}
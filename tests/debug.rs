@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use ::ezplatform::{
+    debug::*,
+    rendering::{BLUE, GREEN, RED},
+};
+use ggez::{graphics::Rect, mint::Point2};
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_debug_colors() {
+    assert_eq!(ggez::graphics::Color::new(0.0, 1.0, 0.0, 1.0), GREEN);
+    let colors = DebugColors::default();
+    assert_eq!(GREEN, colors.colliders);
+    assert_eq!(BLUE, colors.velocity);
+    assert_eq!(RED, colors.ground_checks);
+
+    let mut overlay = DebugOverlay::new();
+    assert!(!overlay.is_enabled());
+    overlay.toggle();
+    assert!(overlay.is_enabled());
+    overlay.colors_mut().colliders = RED;
+    assert_eq!(RED, overlay.colors().colliders);
+}
+
+#[test]
+fn test_frame_time_graph_statistics() {
+    let mut graph = FrameTimeGraph::new(3);
+    assert_eq_float!(0.0, graph.fps(), DELTA);
+    assert_eq!(None, graph.latest());
+
+    graph.push(Duration::from_secs_f32(0.01));
+    graph.push(Duration::from_secs_f32(0.02));
+    graph.push(Duration::from_secs_f32(0.03));
+    assert_eq_float!(0.02, graph.average(), DELTA);
+    assert_eq_float!(50.0, graph.fps(), 0.001);
+
+    graph.push(Duration::from_secs_f32(0.04));
+    assert_eq!(3, graph.samples().len());
+    assert_eq_float!(0.04, graph.max(), DELTA);
+    assert_eq_float!(0.04, graph.latest().unwrap(), DELTA);
+}
+
+#[test]
+fn test_frame_time_graph_points() {
+    let mut graph = FrameTimeGraph::new(3);
+    graph.push(Duration::from_secs_f32(0.0));
+    graph.push(Duration::from_secs_f32(0.05));
+    graph.push(Duration::from_secs_f32(0.2));
+
+    let points = graph.points(Rect::new(10.0, 20.0, 100.0, 50.0), 0.1);
+    assert_eq!(3, points.len());
+    assert_eq_point!(Point2 { x: 10.0, y: 70.0 }, points[0], DELTA);
+    assert_eq_point!(Point2 { x: 60.0, y: 45.0 }, points[1], DELTA);
+    assert_eq_point!(Point2 { x: 110.0, y: 20.0 }, points[2], DELTA);
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use ::ezplatform::{debug_draw::*, rendering::RED};
+use ggez::{graphics::Rect, mint::Point2};
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_disabled_buffer_ignores_commands() {
+    let mut debug_draw = DebugDraw::new();
+    debug_draw.line(Point2 { x: 0.0, y: 0.0 }, Point2 { x: 1.0, y: 1.0 }, RED);
+    assert!(debug_draw.is_empty());
+
+    debug_draw.set_enabled(true);
+    debug_draw.rect(Rect::new(0.0, 0.0, 1.0, 1.0), RED);
+    debug_draw.text(Point2 { x: 0.0, y: 0.0 }, "hit", RED);
+    assert_eq!(2, debug_draw.len());
+    assert_eq!(
+        DebugShape::text(Point2 { x: 0.0, y: 0.0 }, "hit"),
+        debug_draw.commands()[1].shape
+    );
+
+    debug_draw.set_enabled(false);
+    assert!(debug_draw.is_empty());
+}
+
+#[test]
+fn test_single_frame_commands_survive_until_drawn() {
+    let mut debug_draw = DebugDraw::new();
+    debug_draw.set_enabled(true);
+    debug_draw.point(Point2 { x: 1.0, y: 2.0 }, RED);
+
+    debug_draw.update(Duration::from_secs_f32(0.5));
+    assert_eq!(1, debug_draw.len());
+
+    debug_draw.mark_drawn();
+    debug_draw.update(Duration::from_secs_f32(0.016));
+    assert!(debug_draw.is_empty());
+}
+
+#[test]
+fn test_commands_with_lifetime() {
+    let mut debug_draw = DebugDraw::new();
+    debug_draw.set_enabled(true);
+    debug_draw.push(
+        DebugShape::circle(Point2 { x: 0.0, y: 0.0 }, 1.0),
+        RED,
+        Some(Duration::from_secs_f32(0.5)),
+    );
+    debug_draw.mark_drawn();
+
+    debug_draw.update(Duration::from_secs_f32(0.2));
+    assert_eq!(1, debug_draw.len());
+    assert_eq_float!(0.3, debug_draw.commands()[0].remaining, DELTA);
+
+    debug_draw.update(Duration::from_secs_f32(0.3));
+    assert!(debug_draw.is_empty());
+}
+
+#[test]
+fn test_global_buffer() {
+    line(Point2 { x: 0.0, y: 0.0 }, Point2 { x: 1.0, y: 0.0 }, RED);
+    assert!(with_debug_draw(|debug_draw| debug_draw.is_empty()));
+
+    with_debug_draw(|debug_draw| debug_draw.set_enabled(true));
+    line(Point2 { x: 0.0, y: 0.0 }, Point2 { x: 1.0, y: 0.0 }, RED);
+    circle(Point2 { x: 0.0, y: 0.0 }, 2.0, RED);
+    queue(
+        DebugShape::Rect(Rect::new(0.0, 0.0, 1.0, 1.0)),
+        RED,
+        Some(Duration::from_secs(1)),
+    );
+    assert_eq!(3, with_debug_draw(|debug_draw| debug_draw.len()));
+
+    with_debug_draw(|debug_draw| {
+        debug_draw.mark_drawn();
+        debug_draw.update(Duration::from_secs_f32(0.1));
+    });
+    let remaining = with_debug_draw(|debug_draw| debug_draw.commands().to_vec());
+    assert_eq!(1, remaining.len());
+    assert_eq!(
+        DebugShape::Rect(Rect::new(0.0, 0.0, 1.0, 1.0)),
+        remaining[0].shape
+    );
+}
@@ -0,0 +1,111 @@
+use std::f32::consts::PI;
+
+use ::ezplatform::{collision::TilemapCollider, lighting::*};
+use ggez::{
+    graphics::{Color, Rect},
+    mint::Point2,
+};
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+fn angle_of(point: &Point2<f32>) -> f32 {
+    point.y.atan2(point.x).rem_euclid(2.0 * PI)
+}
+
+#[test]
+fn test_light_polygon_without_occluders() {
+    let origin = Point2 { x: 1.0, y: 2.0 };
+    let polygon = light_polygon(origin, 5.0, &[], 16);
+
+    assert_eq!(16, polygon.len());
+    for point in polygon.iter() {
+        let distance = (point.x - origin.x).hypot(point.y - origin.y);
+        assert_eq_float!(5.0, distance, DELTA);
+    }
+}
+
+#[test]
+fn test_light_polygon_blocked_by_occluder() {
+    let origin = Point2 { x: 0.0, y: 0.0 };
+    let wall = Rect::new(3.0, 0.0, 2.0, 10.0);
+    let polygon = light_polygon(origin, 5.0, &[wall], 8);
+
+    let right = polygon
+        .iter()
+        .find(|point| angle_of(point) < 0.001)
+        .unwrap();
+    assert_eq_point!(Point2 { x: 2.0, y: 0.0 }, *right, DELTA);
+
+    let left = polygon
+        .iter()
+        .find(|point| (angle_of(point) - PI).abs() < 0.001)
+        .unwrap();
+    assert_eq_point!(Point2 { x: -5.0, y: 0.0 }, *left, DELTA);
+
+    let mut angles: Vec<f32> = polygon.iter().map(angle_of).collect();
+    let sorted = angles.clone();
+    angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(sorted, angles);
+}
+
+#[test]
+fn test_light_polygon_ignores_occluder_around_light() {
+    let origin = Point2 { x: 0.0, y: 0.0 };
+    let tile = Rect::new(0.0, 0.0, 1.0, 1.0);
+    let polygon = light_polygon(origin, 3.0, &[tile], 4);
+
+    assert_eq!(4, polygon.len());
+    assert_eq_point!(Point2 { x: 3.0, y: 0.0 }, polygon[0], DELTA);
+}
+
+#[test]
+fn test_point_light_falloff() {
+    let mut light = PointLight::new(
+        Point2 { x: 0.0, y: 0.0 },
+        4.0,
+        Color::new(1.0, 1.0, 1.0, 1.0),
+    );
+    light.intensity = 0.5;
+
+    assert_eq_float!(0.5, light.falloff(Point2 { x: 0.0, y: 0.0 }), DELTA);
+    assert_eq_float!(0.25, light.falloff(Point2 { x: 0.0, y: 2.0 }), DELTA);
+    assert_eq_float!(0.0, light.falloff(Point2 { x: 6.0, y: 0.0 }), DELTA);
+    assert_eq_rect!(Rect::new(0.0, 0.0, 8.0, 8.0), light.bounds(), DELTA);
+}
+
+#[test]
+fn test_occluders_shared_between_lights() {
+    let floor = [true; 12];
+    let collider = TilemapCollider::from_template(&[&floor[..]]);
+    let mut light_map = LightMap::new(0.2);
+    light_map.add_light(PointLight::new(
+        Point2 { x: 2.0, y: 1.0 },
+        2.0,
+        Color::new(1.0, 1.0, 1.0, 1.0),
+    ));
+    light_map.occlude_tilemaps(&[&collider]);
+    let single = light_map.occluders().to_vec();
+
+    light_map.add_light(PointLight::new(
+        Point2 { x: 2.5, y: 1.0 },
+        2.0,
+        Color::new(1.0, 1.0, 1.0, 1.0),
+    ));
+    light_map.add_light(PointLight::new(
+        Point2 { x: 9.0, y: 1.0 },
+        1.0,
+        Color::new(1.0, 1.0, 1.0, 1.0),
+    ));
+    light_map.occlude_tilemaps(&[&collider]);
+    assert_eq!(single.len(), light_map.occluders().len());
+
+    let far = light_map.lights()[2];
+    let near = light_map.light_occluders(&light_map.lights()[0]);
+    assert!(!near.is_empty());
+    for occluder in light_map.light_occluders(&far).iter() {
+        assert!((occluder.x - far.position.x).abs() <= (occluder.w / 2.0) + far.radius);
+    }
+}
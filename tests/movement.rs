@@ -0,0 +1,109 @@
+use std::{env, fs, time::Duration};
+
+use ::ezplatform::{collision::DynamicCollider, movement::*, physics::PhysicsObject};
+use ggez::{
+    graphics::Rect,
+    mint::{Point2, Vector2},
+};
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+fn params() -> MovementParams {
+    MovementParams {
+        move_force: 10.0,
+        jump_impulse: 5.0,
+        max_speed: 4.0,
+        move_speed_decay: 20.0,
+        jump_decay: 2.0,
+        gravity_acceleration: 9.0,
+    }
+}
+
+#[test]
+fn test_movement_params_fields() {
+    let mut params = params();
+    for field in MovementParams::FIELDS.iter() {
+        assert!(params.get(field).is_some());
+    }
+    assert!(params.set("max_speed", 8.0));
+    assert_eq_float!(8.0, params.max_speed, DELTA);
+    assert!(!params.set("unknown", 1.0));
+    assert_eq!(None, params.get("unknown"));
+}
+
+#[test]
+fn test_movement_params_json() {
+    let params = params();
+    let parsed = MovementParams::from_json(&params.to_json(), params).unwrap();
+    assert_eq!(params, parsed);
+
+    let mut tuned = params;
+    tuned.jump_impulse = 21.6;
+    assert!(tuned.to_json().contains("\"jump_impulse\": 21.6,"));
+
+    let partial = MovementParams::from_json(r#"{ "jump_impulse": 7.5 }"#, params).unwrap();
+    assert_eq_float!(7.5, partial.jump_impulse, DELTA);
+    assert_eq_float!(params.move_force, partial.move_force, DELTA);
+
+    assert!(MovementParams::from_json(r#"{ "jump_impulse": "high" }"#, params).is_err());
+    assert!(MovementParams::from_json(r#"{ "jump_height": 1.0 }"#, params).is_err());
+    assert!(MovementParams::from_json("[1, 2]", params).is_err());
+}
+
+#[test]
+fn test_movement_config_reload() {
+    let path = env::temp_dir().join(format!("ezplatform_movement_{}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let mut config = MovementConfig::new(&path);
+    assert!(config.reload_if_changed(params()).is_none());
+
+    let mut tuned = params();
+    tuned.max_speed = 6.0;
+    config.save(&tuned).unwrap();
+    assert!(config.reload_if_changed(params()).is_none());
+
+    fs::write(&path, r#"{ "max_speed": 3.0 }"#).unwrap();
+    let modified = fs::metadata(&path).unwrap().modified().unwrap();
+    let mut config = MovementConfig::new(&path);
+    config.set_poll_interval(Duration::from_secs(1));
+    assert!(config
+        .poll(Duration::from_secs_f32(0.5), params())
+        .is_none());
+    let reloaded = config
+        .poll(Duration::from_secs_f32(0.5), params())
+        .unwrap()
+        .unwrap();
+    assert_eq_float!(3.0, reloaded.max_speed, DELTA);
+    assert_eq_float!(params().jump_impulse, reloaded.jump_impulse, DELTA);
+    assert_eq!(modified, fs::metadata(&path).unwrap().modified().unwrap());
+    assert!(config.reload_if_changed(params()).is_none());
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_controller_uses_params() {
+    let body = DynamicCollider::from_rect(Rect::new(0.0, 0.0, 1.0, 1.0), 1.0);
+    let mut controller =
+        MovementController::from_params(body, params(), &[Vector2 { x: 0.0, y: -1.0 }]);
+    assert_eq_point!(
+        Point2 { x: 0.0, y: -0.5 },
+        controller.ground_check_points()[0],
+        DELTA
+    );
+
+    controller.jump();
+    assert_eq_float!(5.0, controller.collider().velocity().y, DELTA);
+    controller.release_jump(Duration::from_secs_f32(0.1));
+    assert_eq_float!(4.0, controller.collider().velocity().y, DELTA);
+
+    let mut tuned = params();
+    tuned.jump_impulse = 9.0;
+    controller.set_params(tuned);
+    assert_eq!(tuned, *controller.params());
+    controller.jump();
+    assert_eq_float!(9.0, controller.collider().velocity().y, DELTA);
+}
@@ -0,0 +1,120 @@
+use std::{f32::consts::PI, time::Duration};
+
+use ::ezplatform::{collision::TilemapCollider, particles::*};
+use ggez::{
+    graphics::Color,
+    mint::{Point2, Vector2},
+};
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+fn still_settings() -> EmitterSettings {
+    EmitterSettings {
+        rate: 0.0,
+        lifetime: (1.0, 1.0),
+        speed: (0.0, 0.0),
+        ..EmitterSettings::default()
+    }
+}
+
+#[test]
+fn test_emitter_rate_and_burst() {
+    let mut emitter = ParticleEmitter::new(
+        EmitterSettings {
+            rate: 10.0,
+            max_particles: 8,
+            ..still_settings()
+        },
+        Point2 { x: 0.0, y: 0.0 },
+    );
+
+    emitter.update(Duration::from_secs_f32(0.35));
+    assert_eq!(3, emitter.particles().len());
+
+    emitter.burst(10);
+    assert_eq!(8, emitter.particles().len());
+
+    emitter.set_emitting(false);
+    emitter.update(Duration::from_secs_f32(0.9));
+    assert_eq!(5, emitter.particles().len());
+    emitter.update(Duration::from_secs_f32(0.2));
+    assert!(emitter.particles().is_empty());
+}
+
+#[test]
+fn test_emitter_seeded_velocity_cone() {
+    let settings = EmitterSettings {
+        speed: (1.0, 2.0),
+        direction: PI / 2.0,
+        spread: PI / 4.0,
+        ..still_settings()
+    };
+    let mut first = ParticleEmitter::new(settings, Point2 { x: 0.0, y: 0.0 });
+    let mut second = ParticleEmitter::new(settings, Point2 { x: 0.0, y: 0.0 });
+    first.set_seed(7);
+    second.set_seed(7);
+    first.burst(20);
+    second.burst(20);
+
+    assert_eq!(first.particles(), second.particles());
+    for particle in first.particles() {
+        let speed = particle.velocity.x.hypot(particle.velocity.y);
+        let angle = particle.velocity.y.atan2(particle.velocity.x);
+        assert!((1.0..=2.0).contains(&speed));
+        assert!((PI / 4.0..=3.0 * PI / 4.0).contains(&angle));
+    }
+}
+
+#[test]
+fn test_emitter_gravity_and_lifetime_lerp() {
+    let mut emitter = ParticleEmitter::new(
+        EmitterSettings {
+            gravity: Vector2 { x: 0.0, y: -2.0 },
+            start_color: Color::new(1.0, 0.0, 0.0, 1.0),
+            end_color: Color::new(0.0, 0.0, 1.0, 0.0),
+            start_size: 1.0,
+            end_size: 3.0,
+            ..still_settings()
+        },
+        Point2 { x: 1.0, y: 1.0 },
+    );
+    emitter.burst(1);
+    emitter.update(Duration::from_secs_f32(0.5));
+
+    let particle = emitter.particles()[0];
+    assert_eq_float!(-1.0, particle.velocity.y, DELTA);
+    assert_eq_float!(0.5, particle.position.y, DELTA);
+    assert_eq_float!(0.5, particle.progress(), DELTA);
+    assert_eq_float!(2.0, emitter.size(&particle), DELTA);
+    let color = emitter.color(&particle);
+    assert_eq_float!(0.5, color.r, DELTA);
+    assert_eq_float!(0.5, color.b, DELTA);
+    assert_eq_float!(0.5, color.a, DELTA);
+}
+
+#[test]
+fn test_emitter_collides_with_tilemap() {
+    let floor = TilemapCollider::from_template(&[&[true, true, true]]);
+    let mut emitter = ParticleEmitter::new(
+        EmitterSettings {
+            speed: (10.0, 10.0),
+            direction: -PI / 2.0,
+            spread: 0.0,
+            bounce: 0.5,
+            ..still_settings()
+        },
+        Point2 { x: 1.0, y: 2.0 },
+    );
+    emitter.burst(1);
+
+    for _ in 0..3 {
+        emitter.update_with_colliders(Duration::from_secs_f32(0.1), &[&floor]);
+    }
+
+    let particle = emitter.particles()[0];
+    assert!(particle.position.y > 0.5);
+    assert!(particle.velocity.y > 0.0);
+}
@@ -0,0 +1,122 @@
+use ezplatform::{pixel_perfect::*, rendering::WorldDrawParams, world::World};
+use ggez::{graphics::Rect, mint::Point2};
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_pixel_perfect_distance() {
+    let pixel_perfect = PixelPerfect::new(320, 180, 16.0);
+    assert_eq_float!(5.625, pixel_perfect.distance(), DELTA);
+
+    let pixel_perfect = PixelPerfect::new(180, 320, 16.0);
+    assert_eq_float!(5.625, pixel_perfect.distance(), DELTA);
+}
+
+#[test]
+fn test_pixel_perfect_configure_world() {
+    let pixel_perfect = PixelPerfect::new(320, 180, 16.0);
+    let mut world = World::new(1200.0, 600.0, 7.0);
+    pixel_perfect.configure(&mut world);
+
+    assert_eq_float!(320.0, world.screen_width(), DELTA);
+    assert_eq_float!(180.0, world.screen_height(), DELTA);
+    assert_eq_float!(20.0, world.width(), DELTA);
+    assert_eq_float!(11.25, world.height(), DELTA);
+    assert!(world.pixel_snap());
+}
+
+#[test]
+fn test_pixel_perfect_scale_factor() {
+    let pixel_perfect = PixelPerfect::new(320, 180, 16.0);
+
+    assert_eq!(1, pixel_perfect.scale_factor(100.0, 100.0));
+    assert_eq!(1, pixel_perfect.scale_factor(320.0, 180.0));
+    assert_eq!(3, pixel_perfect.scale_factor(1000.0, 600.0));
+    assert_eq!(6, pixel_perfect.scale_factor(1920.0, 1080.0));
+}
+
+#[test]
+fn test_pixel_perfect_letterbox() {
+    let pixel_perfect = PixelPerfect::new(320, 180, 16.0);
+
+    assert_eq_rect!(
+        Rect::new(0.0, 0.0, 1920.0, 1080.0),
+        pixel_perfect.target_rect(1920.0, 1080.0),
+        DELTA
+    );
+    assert_eq_rect!(
+        Rect::new(20.0, 30.0, 960.0, 540.0),
+        pixel_perfect.target_rect(1000.0, 600.0),
+        DELTA
+    );
+    assert_eq_rect!(
+        Rect::new(80.0, 90.0, 640.0, 360.0),
+        pixel_perfect.target_rect(800.0, 540.0),
+        DELTA
+    );
+}
+
+#[test]
+fn test_pixel_perfect_overlay_coordinates() {
+    let pixel_perfect = PixelPerfect::new(320, 180, 16.0);
+
+    assert_eq_rect!(
+        Rect::new(0.0, 0.0, 320.0, 180.0),
+        pixel_perfect.overlay_coordinates(Rect::new(0.0, 0.0, 1920.0, 1080.0)),
+        DELTA
+    );
+    assert_eq_rect!(
+        Rect::new(-20.0 / 3.0, -10.0, 1000.0 / 3.0, 200.0),
+        pixel_perfect.overlay_coordinates(Rect::new(0.0, 0.0, 1000.0, 600.0)),
+        DELTA
+    );
+}
+
+#[test]
+fn test_pixel_perfect_snap() {
+    let pixel_perfect = PixelPerfect::new(320, 180, 16.0);
+
+    assert_eq_point!(
+        Point2 { x: 1.0625, y: -0.5 },
+        pixel_perfect.snap(Point2 { x: 1.07, y: -0.49 }),
+        DELTA
+    );
+}
+
+#[test]
+fn test_world_pixel_snap() {
+    let mut world = World::new(320.0, 180.0, 5.625);
+    world.set_pixel_snap(true);
+    world.look_at(Point2 { x: 0.01, y: 0.0 });
+
+    let screen_position = world.world_to_screen_pos(Point2 { x: 1.0, y: 1.0 });
+    assert_eq_point!(Point2 { x: 176.0, y: 74.0 }, screen_position, DELTA);
+
+    let screen_rect = world.world_to_screen_rect(Rect::new(1.0, 1.0, 1.01, 0.99));
+    assert_eq_rect!(Rect::new(176.0, 74.0, 16.0, 16.0), screen_rect, DELTA);
+}
+
+#[test]
+fn test_pixel_snap_aligns_sprite_corner() {
+    let mut world = World::new(320.0, 180.0, 5.625);
+    world.set_pixel_snap(true);
+    world.look_at(Point2 { x: 0.01, y: 0.0 });
+    let rect = Rect::new(1.0, 1.0, 15.0 / 16.0, 15.0 / 16.0);
+    let dimensions = Rect::new(0.0, 0.0, 15.0, 15.0);
+
+    let param = WorldDrawParams::default().to_draw_param(&world, rect, dimensions);
+    let left = param.dest.x - param.offset.x * 15.0;
+    let top = param.dest.y - param.offset.y * 15.0;
+    assert_eq_float!(left.round(), left, DELTA);
+    assert_eq_float!(top.round(), top, DELTA);
+
+    let param = WorldDrawParams::new()
+        .flip_x(true)
+        .pivot(Point2 { x: 0.25, y: 0.5 })
+        .to_draw_param(&world, rect, dimensions);
+    let left = param.dest.x - (1.0 - param.offset.x) * 15.0;
+    assert_eq_float!(left.round(), left, DELTA);
+}
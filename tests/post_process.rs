@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use ::ezplatform::post_process::*;
+use ggez::graphics::Color;
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_effect_uniforms() {
+    let crt = PostEffect::Crt {
+        scanlines: 0.3,
+        curvature: 0.1,
+        flicker: 0.02,
+    };
+    let uniforms = crt.uniforms([600.0, 300.0], 2.5);
+    assert_eq!(uniforms.resolution, [600.0, 300.0]);
+    assert_eq_float!(2.5, uniforms.time, DELTA);
+    assert_eq_float!(0.3, uniforms.strength, DELTA);
+    assert_eq_float!(0.1, uniforms.params[0], DELTA);
+    assert_eq_float!(0.02, uniforms.params[1], DELTA);
+
+    let grade = PostEffect::ColorGrade {
+        tint: Color::new(1.0, 0.5, 0.25, 1.0),
+        saturation: 0.8,
+        contrast: 1.1,
+        brightness: -0.1,
+    };
+    let uniforms = grade.uniforms([1.0, 1.0], 0.0);
+    assert_eq!(uniforms.color, [1.0, 0.5, 0.25, 1.0]);
+    assert_eq_float!(0.8, uniforms.params[0], DELTA);
+    assert_eq_float!(1.1, uniforms.params[1], DELTA);
+    assert_eq_float!(-0.1, uniforms.params[2], DELTA);
+}
+
+#[test]
+fn test_chain_toggles_effects_in_order() {
+    let mut chain = PostProcessChain::new();
+    let vignette = chain.add_effect(PostEffect::Vignette {
+        strength: 0.5,
+        radius: 0.75,
+        softness: 0.4,
+    });
+    let pixelate = chain.add_effect(PostEffect::Pixelate { pixel_size: 4.0 });
+    assert_eq!(chain.find(PostEffectKind::Pixelate), Some(pixelate));
+    assert_eq!(chain.active_effects().len(), 2);
+    assert_eq!(chain.active_effects()[0].kind(), PostEffectKind::Vignette);
+
+    chain.toggle(PostEffectKind::Vignette);
+    assert!(!chain.is_enabled(vignette));
+    let active = chain.active_effects();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].kind(), PostEffectKind::Pixelate);
+
+    chain.set_enabled(vignette, true);
+    if let Some(PostEffect::Pixelate { pixel_size }) = chain.effect_mut(pixelate) {
+        *pixel_size = 1.0;
+    }
+    let active = chain.active_effects();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].kind(), PostEffectKind::Vignette);
+}
+
+#[test]
+fn test_flash_decays() {
+    let mut chain = PostProcessChain::new();
+    assert!(chain.active_effects().is_empty());
+
+    chain.trigger_flash(Color::new(1.0, 0.0, 0.0, 1.0), Duration::from_secs_f32(0.5));
+    let flash = chain.find(PostEffectKind::Flash).unwrap();
+    assert_eq!(chain.active_effects().len(), 1);
+
+    chain.update(Duration::from_secs_f32(0.25));
+    match chain.effect(flash) {
+        Some(PostEffect::Flash { amount, .. }) => {
+            assert_eq_float!(0.5, *amount, DELTA);
+        }
+        _ => panic!("expected a flash effect"),
+    }
+    assert_eq_float!(0.25, chain.time(), DELTA);
+
+    chain.update(Duration::from_secs_f32(0.5));
+    assert!(chain.active_effects().is_empty());
+    chain.trigger_flash(ggez::graphics::WHITE, Duration::from_secs_f32(0.5));
+    assert_eq!(chain.find(PostEffectKind::Flash), Some(flash));
+}
@@ -0,0 +1,55 @@
+use std::cmp::Ordering;
+
+use ::ezplatform::render_queue::*;
+
+#[test]
+fn test_render_queue_draw_order() {
+    let mut queue = RenderQueue::new();
+    let background = queue.submit_fn(0, SortKey::Z(5), |_, _| Ok(()));
+    let front = queue.submit_fn(2, SortKey::Z(0), |_, _| Ok(()));
+    let high = queue.submit_fn(1, SortKey::Y(4.0), |_, _| Ok(()));
+    let low = queue.submit_fn(1, SortKey::Y(-2.0), |_, _| Ok(()));
+    let low_again = queue.submit_fn(1, SortKey::Y(-2.0), |_, _| Ok(()));
+
+    assert_eq!(5, queue.len());
+    assert_eq!(
+        vec![background, high, low, low_again, front],
+        queue.draw_order()
+    );
+}
+
+#[test]
+fn test_render_queue_groups_sort_key_kinds() {
+    let mut queue = RenderQueue::new();
+    let far = queue.submit_fn(0, SortKey::Y(100.0), |_, _| Ok(()));
+    let z = queue.submit_fn(0, SortKey::Z(-50), |_, _| Ok(()));
+    let near = queue.submit_fn(0, SortKey::Y(-100.0), |_, _| Ok(()));
+    let z_front = queue.submit_fn(0, SortKey::Z(200), |_, _| Ok(()));
+
+    assert_eq!(vec![z, z_front, far, near], queue.draw_order());
+}
+
+#[test]
+fn test_sort_key_value() {
+    assert!(SortKey::Z(1).value() < SortKey::Z(2).value());
+    assert!(SortKey::Y(3.0).value() < SortKey::Y(1.0).value());
+    assert_eq!(Ordering::Less, SortKey::Z(500).compare(&SortKey::Y(0.0)));
+    assert_eq!(Ordering::Greater, SortKey::Y(9.0).compare(&SortKey::Z(-9)));
+}
+
+#[test]
+fn test_batch_ranges() {
+    let keys = [
+        Some(1),
+        Some(1),
+        None,
+        None,
+        Some(2),
+        Some(1),
+        Some(1),
+        Some(1),
+    ];
+
+    assert_eq!(vec![0..2, 2..3, 3..4, 4..5, 5..8], batch_ranges(&keys));
+    assert!(batch_ranges::<u32>(&[]).is_empty());
+}
@@ -0,0 +1,140 @@
+use ezplatform::{rendering::*, world::World};
+use ggez::{
+    graphics::{Color, Rect},
+    mint::Point2,
+};
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_sprite_frame_grid() {
+    let frames = SpriteFrame::grid(64.0, 96.0, 3, 2, 5);
+
+    assert_eq!(5, frames.len());
+    assert_eq_rect!(Rect::new(0.0, 0.0, 32.0, 32.0), frames[0].rect, DELTA);
+    assert_eq_rect!(Rect::new(32.0, 0.0, 32.0, 32.0), frames[1].rect, DELTA);
+    assert_eq_rect!(Rect::new(0.0, 32.0, 32.0, 32.0), frames[2].rect, DELTA);
+    assert_eq_rect!(Rect::new(0.0, 64.0, 32.0, 32.0), frames[4].rect, DELTA);
+    assert_eq_point!(Point2 { x: 0.5, y: 0.5 }, frames[4].pivot, DELTA);
+}
+
+#[test]
+fn test_sprite_frame_src_rect() {
+    let frame =
+        SpriteFrame::with_pivot(Rect::new(16.0, 32.0, 16.0, 32.0), Point2 { x: 0.5, y: 1.0 });
+
+    assert_eq_rect!(
+        Rect::new(0.25, 0.5, 0.25, 0.5),
+        frame.src_rect(64.0, 64.0),
+        DELTA
+    );
+}
+
+#[test]
+fn test_world_draw_params_color() {
+    let params = WorldDrawParams::new()
+        .tint(Color::new(1.0, 0.5, 0.25, 0.8))
+        .alpha(0.5);
+    let color = params.color();
+
+    assert_eq_float!(0.5, color.g, DELTA);
+    assert_eq_float!(0.25, color.b, DELTA);
+    assert_eq_float!(0.4, color.a, DELTA);
+}
+
+#[test]
+fn test_world_draw_params_untransformed() {
+    assert!(WorldDrawParams::new()
+        .tint(Color::new(1.0, 0.0, 0.0, 1.0))
+        .alpha(0.5)
+        .z(3)
+        .is_untransformed());
+    assert!(!WorldDrawParams::new().flip_x(true).is_untransformed());
+    assert!(!WorldDrawParams::new().flip_y(true).is_untransformed());
+    assert!(!WorldDrawParams::new().rotation(0.5).is_untransformed());
+    assert!(!WorldDrawParams::new()
+        .pivot(Point2 { x: 0.0, y: 0.0 })
+        .is_untransformed());
+}
+
+#[test]
+fn test_world_draw_params_place_pivot() {
+    let world = World::new(800.0, 400.0, 8.0);
+    let rect = Rect::new(2.0, 1.0, 1.0, 2.0);
+    let dimensions = Rect::new(0.0, 0.0, 32.0, 32.0);
+    let pivot = Point2 { x: 0.25, y: 1.0 };
+    let position = world.world_to_screen_pos(Point2 { x: 2.0, y: 1.0 });
+    let target_rect = world.world_to_screen_rect(rect);
+
+    let draw_param = WorldDrawParams::new()
+        .pivot(pivot)
+        .to_draw_param(&world, rect, dimensions);
+    assert_eq_point!(pivot, draw_param.offset, DELTA);
+    assert_eq_point!(position, draw_param.dest, DELTA);
+
+    let flipped = WorldDrawParams::new()
+        .pivot(pivot)
+        .flip_x(true)
+        .to_draw_param(&world, rect, dimensions);
+    assert_eq_point!(pivot, flipped.offset, DELTA);
+    assert_eq_point!(position, flipped.dest, DELTA);
+    assert_eq_float!(-target_rect.w / 32.0, flipped.scale.x, DELTA);
+    assert_eq_float!(target_rect.h / 32.0, flipped.scale.y, DELTA);
+}
+
+#[test]
+fn test_trimmed_frames_share_sheet_scale() {
+    let world = World::new(800.0, 400.0, 8.0);
+    let rect = Rect::new(2.0, 1.0, 1.0, 2.0);
+    let frames = [
+        SpriteFrame::with_pivot(Rect::new(0.0, 0.0, 16.0, 32.0), Point2 { x: 0.5, y: 1.0 }),
+        SpriteFrame::with_pivot(Rect::new(16.0, 0.0, 8.0, 12.0), Point2 { x: 0.25, y: 1.0 }),
+    ];
+    let source = SpriteFrame::source_rect(&frames);
+    assert_eq_rect!(Rect::new(0.0, 0.0, 16.0, 32.0), source, DELTA);
+
+    let params: Vec<_> = frames
+        .iter()
+        .map(|frame| {
+            WorldDrawParams::new()
+                .pivot(frame.pivot)
+                .to_frame_draw_param(&world, rect, frame.rect, source)
+        })
+        .collect();
+    let target_rect = world.world_to_screen_rect(rect);
+    for param in params.iter() {
+        assert_eq_float!(target_rect.w / 16.0, param.scale.x, DELTA);
+        assert_eq_float!(target_rect.h / 32.0, param.scale.y, DELTA);
+        assert_eq_point!(
+            world.world_to_screen_pos(Point2 { x: 2.0, y: 1.0 }),
+            param.dest,
+            DELTA
+        );
+    }
+    assert_eq_point!(frames[1].pivot, params[1].offset, DELTA);
+}
+
+#[test]
+fn test_world_draw_params_default_pivot() {
+    let world = World::new(800.0, 400.0, 8.0);
+    let rect = Rect::new(2.0, 1.0, 1.0, 2.0);
+    let dimensions = Rect::new(0.0, 0.0, 32.0, 32.0);
+    let frame_pivot = Point2 { x: 0.5, y: 1.0 };
+
+    let params = WorldDrawParams::new();
+    assert_eq_point!(Point2 { x: 0.5, y: 0.5 }, params.pivot_or_center(), DELTA);
+    let draw_param = params
+        .default_pivot(frame_pivot)
+        .to_draw_param(&world, rect, dimensions);
+    assert_eq_point!(frame_pivot, draw_param.offset, DELTA);
+
+    let custom = Point2 { x: 0.25, y: 0.25 };
+    let draw_param = WorldDrawParams::new()
+        .pivot(custom)
+        .default_pivot(frame_pivot)
+        .to_draw_param(&world, rect, dimensions);
+    assert_eq_point!(custom, draw_param.offset, DELTA);
+}
@@ -0,0 +1,78 @@
+use ezplatform::{scaling::*, world::World};
+use ggez::graphics::Rect;
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_stretch_policy() {
+    let scaler = ScreenScaler::new(ScalingPolicy::Stretch, 800.0, 400.0, 8.0);
+    let mut world = World::new(800.0, 400.0, 8.0);
+
+    let viewport = scaler.apply(&mut world, 1000.0, 1000.0);
+    assert_eq_rect!(Rect::new(0.0, 0.0, 1000.0, 1000.0), viewport, DELTA);
+    assert_eq_float!(1000.0, world.screen_width(), DELTA);
+    assert_eq_float!(1000.0, world.screen_height(), DELTA);
+    assert_eq_float!(32.0, world.width(), DELTA);
+    assert_eq_float!(16.0, world.height(), DELTA);
+}
+
+#[test]
+fn test_stretch_survives_distance_changes() {
+    let mut scaler = ScreenScaler::new(ScalingPolicy::Stretch, 800.0, 400.0, 8.0);
+    let mut world = World::new(800.0, 400.0, 8.0);
+    scaler.apply(&mut world, 1000.0, 1000.0);
+
+    world.set_distance(4.0);
+    assert_eq_float!(16.0, world.width(), DELTA);
+    assert_eq_float!(8.0, world.height(), DELTA);
+
+    world.set_screen_dims(500.0, 1000.0);
+    assert_eq_float!(16.0, world.width(), DELTA);
+    assert_eq_float!(8.0, world.height(), DELTA);
+
+    scaler.set_policy(ScalingPolicy::Expand);
+    scaler.apply(&mut world, 800.0, 400.0);
+    world.set_distance(4.0);
+    assert_eq_float!(16.0, world.width(), DELTA);
+    assert_eq_float!(8.0, world.height(), DELTA);
+    world.set_screen_dims(400.0, 400.0);
+    assert_eq_float!(8.0, world.width(), DELTA);
+    assert_eq_float!(8.0, world.height(), DELTA);
+}
+
+#[test]
+fn test_letterbox_policy() {
+    let scaler = ScreenScaler::new(ScalingPolicy::Letterbox(2.0), 800.0, 400.0, 8.0);
+    let mut world = World::new(800.0, 400.0, 8.0);
+
+    let viewport = scaler.apply(&mut world, 1000.0, 1000.0);
+    assert_eq_rect!(Rect::new(0.0, 250.0, 1000.0, 500.0), viewport, DELTA);
+    assert_eq_float!(1000.0, world.screen_width(), DELTA);
+    assert_eq_float!(500.0, world.screen_height(), DELTA);
+    assert_eq_float!(32.0, world.width(), DELTA);
+    assert_eq_float!(16.0, world.height(), DELTA);
+
+    let viewport = scaler.apply(&mut world, 1600.0, 400.0);
+    assert_eq_rect!(Rect::new(400.0, 0.0, 800.0, 400.0), viewport, DELTA);
+    assert_eq_float!(32.0, world.width(), DELTA);
+    assert_eq_float!(16.0, world.height(), DELTA);
+}
+
+#[test]
+fn test_expand_policy() {
+    let scaler = ScreenScaler::new(ScalingPolicy::Expand, 800.0, 400.0, 8.0);
+    let mut world = World::new(800.0, 400.0, 8.0);
+
+    let viewport = scaler.apply(&mut world, 1600.0, 800.0);
+    assert_eq_rect!(Rect::new(0.0, 0.0, 1600.0, 800.0), viewport, DELTA);
+    assert_eq_float!(64.0, world.width(), DELTA);
+    assert_eq_float!(32.0, world.height(), DELTA);
+
+    let viewport = scaler.apply(&mut world, 1200.0, 400.0);
+    assert_eq_rect!(Rect::new(0.0, 0.0, 1200.0, 400.0), viewport, DELTA);
+    assert_eq_float!(48.0, world.width(), DELTA);
+    assert_eq_float!(16.0, world.height(), DELTA);
+}
@@ -0,0 +1,154 @@
+use ::ezplatform::ui::*;
+use ggez::{
+    event::{Button as GamepadButton, KeyCode},
+    graphics::Rect,
+    mint::Vector2,
+};
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_anchor_placement() {
+    let screen = Rect::new(10.0, 20.0, 800.0, 600.0);
+    let no_offset = Vector2 { x: 0.0, y: 0.0 };
+
+    let top_left = Anchor::TopLeft.place(screen, Vector2 { x: 5.0, y: 5.0 }, (100.0, 50.0));
+    assert_eq_rect!(Rect::new(15.0, 25.0, 100.0, 50.0), top_left, DELTA);
+
+    let center = Anchor::Center.place(screen, no_offset, (100.0, 50.0));
+    assert_eq_rect!(Rect::new(360.0, 295.0, 100.0, 50.0), center, DELTA);
+
+    let bottom_right =
+        Anchor::BottomRight.place(screen, Vector2 { x: -5.0, y: 0.0 }, (100.0, 50.0));
+    assert_eq_rect!(Rect::new(705.0, 570.0, 100.0, 50.0), bottom_right, DELTA);
+}
+
+#[test]
+fn test_layer_margin() {
+    let mut layer = UiLayer::new(800.0, 600.0);
+    layer.set_margin(16.0);
+    assert_eq_rect!(
+        Rect::new(0.0, 0.0, 800.0, 600.0),
+        layer.screen_rect(),
+        DELTA
+    );
+    assert_eq_rect!(
+        Rect::new(16.0, 16.0, 768.0, 568.0),
+        layer.layout_rect(),
+        DELTA
+    );
+
+    layer.resize(20.0, 10.0);
+    assert_eq_rect!(Rect::new(5.0, 5.0, 10.0, 0.0), layer.layout_rect(), DELTA);
+}
+
+#[test]
+fn test_progress_bar_fill() {
+    let mut bar = ProgressBar::new(4.0, 100.0, 10.0);
+    assert_eq_float!(1.0, bar.fraction(), DELTA);
+
+    bar.set_value(1.0);
+    assert_eq_float!(0.25, bar.fraction(), DELTA);
+    let fill = bar.fill_rect(Rect::new(10.0, 10.0, 100.0, 10.0));
+    assert_eq_rect!(Rect::new(10.0, 10.0, 25.0, 10.0), fill, DELTA);
+
+    bar.set_value(-3.0);
+    assert_eq_float!(0.0, bar.fraction(), DELTA);
+    bar.set_max(0.0);
+    assert_eq_float!(0.0, bar.fraction(), DELTA);
+}
+
+#[test]
+fn test_menu_navigation() {
+    let mut buttons = vec![
+        Button::new("Resume"),
+        Button::new("Options"),
+        Button::new("Quit"),
+    ];
+    buttons[1].enabled = false;
+    let mut menu = Menu::new(buttons);
+    assert_eq!(0, menu.selected());
+
+    assert_eq!(
+        Some(MenuEvent::Selected(2)),
+        menu.handle_input(UiInput::Down)
+    );
+    assert_eq!(
+        Some(MenuEvent::Selected(0)),
+        menu.handle_input(UiInput::Down)
+    );
+    assert_eq!(Some(MenuEvent::Selected(2)), menu.handle_input(UiInput::Up));
+    assert_eq!(
+        Some(MenuEvent::Activated(2)),
+        menu.handle_input(UiInput::Confirm)
+    );
+    assert_eq!(
+        Some(MenuEvent::Cancelled),
+        menu.handle_input(UiInput::Cancel)
+    );
+    assert_eq!(None, menu.handle_input(UiInput::Left));
+
+    menu.select(1);
+    assert_eq!(2, menu.selected());
+}
+
+#[test]
+fn test_menu_without_wrapping() {
+    let mut menu = Menu::with_labels(&["Start", "Quit"]).wrap(false);
+    assert_eq!(None, menu.handle_input(UiInput::Up));
+    assert_eq!(
+        Some(MenuEvent::Selected(1)),
+        menu.handle_input(UiInput::Down)
+    );
+    assert_eq!(None, menu.handle_input(UiInput::Down));
+    assert_eq!(1, menu.selected());
+}
+
+#[test]
+fn test_input_mapping() {
+    assert_eq!(Some(UiInput::Up), UiInput::from_keycode(KeyCode::Up));
+    assert_eq!(
+        Some(UiInput::Confirm),
+        UiInput::from_keycode(KeyCode::Return)
+    );
+    assert_eq!(
+        Some(UiInput::Cancel),
+        UiInput::from_keycode(KeyCode::Escape)
+    );
+    assert_eq!(None, UiInput::from_keycode(KeyCode::F1));
+    assert_eq!(
+        Some(UiInput::Down),
+        UiInput::from_button(GamepadButton::DPadDown)
+    );
+    assert_eq!(
+        Some(UiInput::Confirm),
+        UiInput::from_button(GamepadButton::South)
+    );
+    assert_eq!(
+        Some(UiInput::Cancel),
+        UiInput::from_button(GamepadButton::East)
+    );
+}
+
+#[test]
+fn test_tweak_panel_adjusts_values() {
+    let mut panel = TweakPanel::new(vec![
+        Tweak::new("speed", 1.0, 0.5).with_range(0.0, 2.0),
+        Tweak::new("gravity", 9.0, 1.0),
+    ]);
+    assert_eq!(Some(0), panel.handle_input(UiInput::Right));
+    assert_eq!(Some(0), panel.handle_input(UiInput::Right));
+    assert_eq!(None, panel.handle_input(UiInput::Right));
+    assert_eq_float!(2.0, panel.tweak("speed").unwrap().value, DELTA);
+
+    assert_eq!(None, panel.handle_input(UiInput::Up));
+    assert_eq!(1, panel.selected());
+    assert_eq!(Some(1), panel.handle_input(UiInput::Left));
+    assert_eq_float!(8.0, panel.tweak("gravity").unwrap().value, DELTA);
+
+    panel.set_value("speed", -3.0);
+    assert_eq_float!(0.0, panel.tweak("speed").unwrap().value, DELTA);
+}
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use ezplatform::{camera::SharpCamera, viewport::*};
+use ggez::{graphics::Rect, mint::Point2};
+
+#[macro_use]
+mod float_asserts;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_split_horizontal() {
+    let rects = split_horizontal(800.0, 400.0, 2);
+
+    assert_eq!(2, rects.len());
+    assert_eq_rect!(Rect::new(0.0, 0.0, 400.0, 400.0), rects[0], DELTA);
+    assert_eq_rect!(Rect::new(400.0, 0.0, 400.0, 400.0), rects[1], DELTA);
+}
+
+#[test]
+fn test_split_vertical() {
+    let rects = split_vertical(800.0, 600.0, 3);
+
+    assert_eq!(3, rects.len());
+    assert_eq_rect!(Rect::new(0.0, 0.0, 800.0, 200.0), rects[0], DELTA);
+    assert_eq_rect!(Rect::new(0.0, 200.0, 800.0, 200.0), rects[1], DELTA);
+    assert_eq_rect!(Rect::new(0.0, 400.0, 800.0, 200.0), rects[2], DELTA);
+}
+
+#[test]
+fn test_new_viewport() {
+    let camera = SharpCamera::new(Point2 { x: 3.0, y: 1.0 });
+    let viewport = Viewport::new(Rect::new(400.0, 0.0, 400.0, 400.0), 8.0, Box::new(camera));
+
+    assert_eq_float!(400.0, viewport.world().screen_width(), DELTA);
+    assert_eq_float!(400.0, viewport.world().screen_height(), DELTA);
+    assert_eq_float!(16.0, viewport.world().width(), DELTA);
+    assert_eq_point!(
+        Point2 { x: 3.0, y: 1.0 },
+        viewport.world().camera_position(),
+        DELTA
+    );
+}
+
+#[test]
+fn test_viewport_world_to_screen() {
+    let camera = SharpCamera::new(Point2 { x: 0.0, y: 0.0 });
+    let viewport = Viewport::new(Rect::new(400.0, 100.0, 400.0, 200.0), 4.0, Box::new(camera));
+
+    assert_eq_point!(
+        Point2 { x: 600.0, y: 200.0 },
+        viewport.world_to_screen_pos(Point2 { x: 0.0, y: 0.0 }),
+        DELTA
+    );
+    assert_eq_point!(
+        Point2 { x: 625.0, y: 175.0 },
+        viewport.world_to_screen_pos(Point2 { x: 1.0, y: 1.0 }),
+        DELTA
+    );
+    assert_eq_rect!(
+        Rect::new(625.0, 175.0, 25.0, 25.0),
+        viewport.world_to_screen_rect(Rect::new(1.0, 1.0, 1.0, 1.0)),
+        DELTA
+    );
+    assert_eq_point!(
+        Point2 { x: 1.0, y: 1.0 },
+        viewport.screen_to_world_pos(Point2 { x: 625.0, y: 175.0 }),
+        DELTA
+    );
+    assert!(viewport.contains(Point2 { x: 500.0, y: 150.0 }));
+    assert!(!viewport.contains(Point2 { x: 300.0, y: 150.0 }));
+}
+
+#[test]
+fn test_viewport_follows_camera() {
+    let camera = SharpCamera::new(Point2 { x: 0.0, y: 0.0 });
+    let mut viewport = Viewport::new(Rect::new(0.0, 0.0, 400.0, 400.0), 8.0, Box::new(camera));
+
+    viewport
+        .camera_mut()
+        .set_destination(Point2 { x: 5.0, y: -2.0 });
+    viewport.update(Duration::default());
+    assert_eq_point!(
+        Point2 { x: 5.0, y: -2.0 },
+        viewport.world().camera_position(),
+        DELTA
+    );
+
+    viewport.set_screen_rect(Rect::new(0.0, 0.0, 800.0, 400.0));
+    assert_eq_float!(32.0, viewport.world().width(), DELTA);
+    assert_eq_float!(16.0, viewport.world().height(), DELTA);
+}
+
+#[test]
+fn test_canvas_region() {
+    assert_eq_rect!(
+        Rect::new(0.0, 0.0, 1.0, 1.0),
+        canvas_region(400.0, 200.0, 400.0, 200.0),
+        DELTA
+    );
+    assert_eq_rect!(
+        Rect::new(0.0, 0.75, 0.5, 0.25),
+        canvas_region(400.0, 200.0, 800.0, 800.0),
+        DELTA
+    );
+}
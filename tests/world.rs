@@ -1,5 +1,6 @@
 use ezplatform::world::*;
 use ggez::{graphics::Rect, mint::Point2};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 #[macro_use]
 mod float_asserts;
@@ -359,3 +360,156 @@ fn test_world_to_screen_rect() {
         DELTA
     );
 }
+
+#[test]
+fn test_screen_to_world_pos() {
+    let mut world = World::new(800.0, 400.0, 8.0);
+
+    assert_eq_point!(
+        Point2 { x: 0.0, y: 0.0 },
+        world.screen_to_world_pos(Point2 { x: 400.0, y: 200.0 }),
+        DELTA
+    );
+    assert_eq_point!(
+        Point2 { x: 1.0, y: 1.0 },
+        world.screen_to_world_pos(Point2 { x: 425.0, y: 175.0 }),
+        DELTA
+    );
+
+    world.look_at(Point2 { x: 4.0, y: 8.0 });
+    assert_eq_point!(
+        Point2 { x: -20.0, y: 10.0 },
+        world.screen_to_world_pos(Point2 {
+            x: -200.0,
+            y: 150.0
+        }),
+        DELTA
+    );
+}
+
+#[test]
+fn test_screen_to_world_rect() {
+    let world = World::new(800.0, 400.0, 8.0);
+
+    let screen_rect = Rect::new(425.0, 175.0, 25.0, 50.0);
+    let expected_world_rect = Rect::new(1.0, 1.0, 1.0, 2.0);
+    assert_eq_rect!(
+        expected_world_rect,
+        world.screen_to_world_rect(screen_rect),
+        DELTA
+    );
+}
+
+#[test]
+fn test_world_rotation() {
+    let mut world = World::new(800.0, 400.0, 8.0);
+    world.set_rotation(std::f32::consts::FRAC_PI_2);
+
+    assert_eq_float!(std::f32::consts::FRAC_PI_2, world.rotation(), DELTA);
+    assert_eq_point!(
+        Point2 { x: 400.0, y: 225.0 },
+        world.world_to_screen_pos(Point2 { x: 1.0, y: 0.0 }),
+        0.001
+    );
+    assert_eq_point!(
+        Point2 { x: 425.0, y: 200.0 },
+        world.world_to_screen_pos(Point2 { x: 0.0, y: 1.0 }),
+        0.001
+    );
+}
+
+#[test]
+fn test_world_non_uniform_dims() {
+    let mut world = World::new(800.0, 400.0, 8.0);
+    world.set_world_dims(16.0, 16.0);
+
+    assert_eq_float!(16.0, world.width(), DELTA);
+    assert_eq_float!(16.0, world.height(), DELTA);
+    assert_eq_float!(8.0, world.distance(), DELTA);
+    assert_eq_rect!(
+        Rect::new(450.0, 175.0, 50.0, 25.0),
+        world.world_to_screen_rect(Rect::new(1.0, 1.0, 1.0, 1.0)),
+        DELTA
+    );
+}
+
+#[test]
+fn test_world_dims_survive_distance_change() {
+    let mut world = World::new(800.0, 400.0, 8.0);
+    world.set_world_dims(12.0, 24.0);
+
+    world.set_distance(12.0);
+    assert_eq_float!(24.0, world.width(), DELTA);
+    assert_eq_float!(48.0, world.height(), DELTA);
+
+    world.set_screen_dims(1000.0, 200.0);
+    assert_eq_float!(24.0, world.width(), DELTA);
+    assert_eq_float!(48.0, world.height(), DELTA);
+
+    world.clear_world_dims();
+    assert_eq_float!(120.0, world.width(), DELTA);
+    assert_eq_float!(24.0, world.height(), DELTA);
+}
+
+#[test]
+fn test_world_screen_round_trip() {
+    let mut rng = StdRng::seed_from_u64(29);
+
+    for _ in 0..200 {
+        let mut world = World::new(
+            rng.gen_range(100.0..2000.0),
+            rng.gen_range(100.0..2000.0),
+            rng.gen_range(1.0..50.0),
+        );
+        world.look_at(Point2 {
+            x: rng.gen_range(-100.0..100.0),
+            y: rng.gen_range(-100.0..100.0),
+        });
+        world.set_rotation(rng.gen_range(-3.2..3.2));
+        if rng.gen_bool(0.5) {
+            world.set_world_dims(rng.gen_range(1.0..50.0), rng.gen_range(1.0..50.0));
+        }
+
+        let world_point = Point2 {
+            x: rng.gen_range(-200.0..200.0),
+            y: rng.gen_range(-200.0..200.0),
+        };
+        let screen_point = world.world_to_screen_pos(world_point);
+        assert_eq_point!(world_point, world.screen_to_world_pos(screen_point), 0.01);
+
+        let screen_point = Point2 {
+            x: rng.gen_range(0.0..world.screen_width()),
+            y: rng.gen_range(0.0..world.screen_height()),
+        };
+        let world_point = world.screen_to_world_pos(screen_point);
+        assert_eq_point!(screen_point, world.world_to_screen_pos(world_point), 0.01);
+
+        let world_rect = Rect::new(
+            world_point.x,
+            world_point.y,
+            rng.gen_range(0.0..10.0),
+            rng.gen_range(0.0..10.0),
+        );
+        let screen_rect = world.world_to_screen_rect(world_rect);
+        assert_eq_rect!(world_rect, world.screen_to_world_rect(screen_rect), 0.01);
+    }
+}
+
+#[test]
+fn test_rotated_non_uniform_rect() {
+    let mut world = World::new(800.0, 400.0, 8.0);
+    world.set_world_dims(16.0, 16.0);
+    world.set_rotation(std::f32::consts::FRAC_PI_2);
+
+    let world_rect = Rect::new(1.0, 2.0, 2.0, 1.0);
+    let screen_rect = world.world_to_screen_rect(world_rect);
+    assert_eq_float!(50.0, screen_rect.w, 0.001);
+    assert_eq_float!(50.0, screen_rect.h, 0.001);
+    assert_eq_rect!(world_rect, world.screen_to_world_rect(screen_rect), 0.001);
+
+    world.set_rotation(std::f32::consts::FRAC_PI_4);
+    let screen_rect = world.world_to_screen_rect(world_rect);
+    assert_eq_float!(2.0 * 1562.5_f32.sqrt(), screen_rect.w, 0.001);
+    assert_eq_float!(1562.5_f32.sqrt(), screen_rect.h, 0.001);
+    assert_eq_rect!(world_rect, world.screen_to_world_rect(screen_rect), 0.001);
+}